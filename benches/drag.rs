@@ -0,0 +1,96 @@
+//! dragging every piece of a 1000 piece puzzle across the board: a headless app stepping
+//! `move_sprite` with the cursor moving each frame, preselected slots changing as pieces pass
+//! over them, then the slot lookups it runs alone: `Grid::get_slot_at` and
+//! `BoardLayout::get_drop_slot` against scanning all slots, run with `cargo bench --bench drag`
+
+use bevy::MinimalPlugins;
+use bevy::app::App;
+use bevy::math::Vec2;
+use my_jigsaw_game::config::total_pieces::TotalPieces;
+use my_jigsaw_game::model::Point;
+use my_jigsaw_game::play::drag_plugin;
+use my_jigsaw_game::play::layout::BoardLayout;
+use my_jigsaw_game::play::pointer::Cursor;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const FRAMES: usize = 240;
+
+/// cursor of every frame, sliding from the top left of the board to its bottom right
+fn cursors(layout: &BoardLayout) -> Vec<Vec2> {
+    (0..FRAMES)
+        .map(|frame| {
+            let progress = frame as f32 / FRAMES as f32;
+            Vec2::new(
+                (progress - 0.5) * layout.get_board_width(),
+                (0.5 - progress) * layout.get_board_height(),
+            )
+        })
+        .collect()
+}
+
+/// one app update per cursor, returns the time per frame
+fn step(app: &mut App, cursors: &[Vec2]) -> Duration {
+    let start = Instant::now();
+    for cursor in cursors {
+        app.world_mut().resource_mut::<Cursor>().0 = Some(*cursor);
+        app.update();
+    }
+    start.elapsed() / cursors.len() as u32
+}
+
+/// run `find` for every piece in hand on every frame, returns the candidates found and the
+/// time per frame
+fn drag(positions: &[Vec<Vec2>], mut find: impl FnMut(Vec2) -> Option<usize>) -> (usize, Duration) {
+    let start = Instant::now();
+    let mut found = 0;
+    for frame in positions {
+        for position in frame {
+            found += black_box(find(*position)).map_or(0, |_| 1);
+        }
+    }
+    (found, start.elapsed() / FRAMES as u32)
+}
+
+fn main() {
+    let layout = BoardLayout::new(&TotalPieces::P1000, 1920, 1280);
+    let grid = *layout.get_grid();
+    let cursors = cursors(&layout);
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(layout)
+        .add_plugins(drag_plugin);
+    // spawns the slots and the pieces in hand
+    app.update();
+    let frame = step(&mut app, &cursors);
+    println!(
+        "{} pieces in hand over {FRAMES} frames, move_sprite with preselection per frame: {frame:?}",
+        layout.get_count(),
+    );
+
+    // the pieces keep their place relative to each other and slide across the board
+    let offsets: Vec<Vec2> = (0..layout.get_count())
+        .map(|index| layout.get_slot_center(index) * 0.5)
+        .collect();
+    let positions: Vec<Vec<Vec2>> = cursors
+        .iter()
+        .map(|cursor| offsets.iter().map(|offset| *cursor + *offset).collect())
+        .collect();
+
+    let (scanned, scan) = drag(&positions, |position| {
+        let point = Point::new(position.x, position.y);
+        (0..grid.get_count()).find(|index| grid.close_enough(grid.get_slot_center(*index) - point))
+    });
+    let (under, slot_at) = drag(&positions, |position| {
+        grid.get_slot_at(Point::new(position.x, position.y))
+    });
+    let (found, drop_slot) = drag(&positions, |position| layout.get_drop_slot(position, 0));
+    // the drop slot is the scan, made cheap
+    assert_eq!(scanned, found);
+
+    println!(
+        "lookups alone, per frame: scan all slots {scan:?} ({scanned} candidates), \
+         Grid::get_slot_at {slot_at:?} ({under} cells), BoardLayout::get_drop_slot {drop_slot:?} ({found} candidates)",
+    );
+}
@@ -0,0 +1,60 @@
+//! time spent building piece textures when a puzzle starts, one texture per piece
+//! against a single sheet of pieces, run with `cargo bench --bench startup`
+
+use bevy::asset::RenderAssetUsages;
+use bevy::image::Image;
+use image::DynamicImage;
+use my_jigsaw_game::config::total_pieces::TotalPieces;
+use my_jigsaw_game::play::cut::PuzzleCut;
+use my_jigsaw_game::play::layout::BoardLayout;
+use std::hint::black_box;
+use std::sync::atomic::AtomicUsize;
+use std::time::{Duration, Instant};
+
+const PICTURE: &str = "assets/resources/1.jpg";
+const RUNS: u32 = 5;
+
+fn mean<F: FnMut()>(mut run: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        run();
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let start = Instant::now();
+    let picture = image::open(PICTURE).expect("bench picture");
+    println!("decode {PICTURE}: {:?}", start.elapsed());
+
+    for total_pieces in [TotalPieces::P96, TotalPieces::P1000] {
+        let layout = BoardLayout::new(&total_pieces, picture.width(), picture.height());
+        let cut = PuzzleCut::new(42, layout.get_columns(), layout.get_rows());
+
+        let per_piece = mean(|| {
+            for index in 0..layout.get_count() {
+                let piece = DynamicImage::ImageRgba8(cut.cut_piece(&picture, index));
+                black_box(Image::from_dynamic(
+                    piece,
+                    true,
+                    RenderAssetUsages::default(),
+                ));
+            }
+        });
+        let sheet = mean(|| {
+            let sheet = DynamicImage::ImageRgba8(cut.cut_sheet(&picture, &AtomicUsize::new(0)));
+            black_box(Image::from_dynamic(
+                sheet,
+                true,
+                RenderAssetUsages::default(),
+            ));
+        });
+
+        println!(
+            "{} ({} pieces): {} textures {per_piece:?}, one sheet {sheet:?}",
+            total_pieces,
+            layout.get_count(),
+            layout.get_count()
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! cut a picture into pieces without starting the game: one transparent png per piece and a
+//! `layout.json` telling where each piece belongs and which pieces are its neighbours
+
+use image::GenericImageView;
+use my_jigsaw_game::config::total_pieces::TotalPieces;
+use my_jigsaw_game::model::Grid;
+use my_jigsaw_game::play::cut::{PieceShape, PuzzleCut};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "usage: jigsaw-cut <picture> [options]
+
+  --pieces <count>   cut in about this many pieces, 24 when missing
+  --seed <number>    seed of the cut, random when missing
+  --out <folder>     where pieces and layout.json are written, <picture name>-pieces when missing
+  --help             show this message
+
+the same picture, piece count and seed always give the same pieces";
+const LAYOUT_FILE: &str = "layout.json";
+
+struct Options {
+    picture: PathBuf,
+    pieces: TotalPieces,
+    seed: u64,
+    out: PathBuf,
+}
+
+/// written to `layout.json`, positions are in pixels of the picture with y growing downward
+#[derive(Serialize)]
+struct Layout {
+    picture: String,
+    width: u32,
+    height: u32,
+    columns: u32,
+    rows: u32,
+    seed: u64,
+    pieces: Vec<PieceLayout>,
+}
+
+#[derive(Serialize)]
+struct PieceLayout {
+    index: usize,
+    file: String,
+    column: u32,
+    row: u32,
+    /// top left corner of the piece image on the picture, the knobs reach past the cell so it
+    /// can be off the picture
+    x: i64,
+    y: i64,
+    /// the cell the piece fills once in place
+    cell: Cell,
+    shape: PieceShape,
+    neighbours: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct Cell {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+fn main() {
+    let options = match parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = cut(&options) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+/// read the arguments following the program name, none when help is asked
+fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut picture = None;
+    let mut pieces = TotalPieces::P24;
+    let mut seed = None;
+    let mut out = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--pieces" => pieces = TotalPieces::parse(&value(&arg, args.next())?)?,
+            "--seed" => {
+                let number = value(&arg, args.next())?;
+                seed = Some(
+                    number
+                        .parse()
+                        .map_err(|_| format!("--seed takes a whole number, not {number}"))?,
+                );
+            }
+            "--out" => out = Some(PathBuf::from(value(&arg, args.next())?)),
+            "--help" | "-h" => return Ok(None),
+            _ if arg.starts_with("--") => return Err(format!("unknown argument {arg}")),
+            _ if picture.is_none() => picture = Some(PathBuf::from(arg)),
+            _ => return Err(format!("one picture at a time, {arg} is one too many")),
+        }
+    }
+
+    let picture = picture.ok_or("no picture to cut")?;
+    let out = out.unwrap_or_else(|| {
+        let stem = picture.file_stem().unwrap_or_default().to_string_lossy();
+        picture.with_file_name(format!("{stem}-pieces"))
+    });
+    Ok(Some(Options {
+        picture,
+        pieces,
+        seed: seed.unwrap_or_else(rand::random),
+        out,
+    }))
+}
+
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{flag} needs a value"))
+}
+
+fn cut(options: &Options) -> Result<(), String> {
+    let picture = image::open(&options.picture)
+        .map_err(|e| format!("can not read {}: {e}", options.picture.display()))?;
+    let (width, height) = picture.dimensions();
+    let (columns, rows) = options.pieces.get_grid(width, height);
+    let cut = PuzzleCut::new(options.seed, columns, rows);
+    let grid = Grid::new(columns, rows, width as f32, height as f32);
+    let (cell_width, cell_height) = cut.get_cell_size(width, height);
+    if cell_width == 0 || cell_height == 0 {
+        return Err(format!(
+            "{} is {width} x {height} pixels, too small for {columns} x {rows} pieces",
+            options.picture.display()
+        ));
+    }
+    let padding = PuzzleCut::get_padding(cell_width as f32, cell_height as f32).ceil() as i64;
+
+    fs::create_dir_all(&options.out)
+        .map_err(|e| format!("can not create {}: {e}", options.out.display()))?;
+
+    let mut pieces = vec![];
+    for index in 0..grid.get_count() {
+        let file = format!("piece-{index:04}.png");
+        save_png(&cut.cut_piece(&picture, index), &options.out.join(&file))?;
+
+        let column = index as u32 % columns;
+        let row = index as u32 / columns;
+        let cell = Cell {
+            x: column * cell_width,
+            y: row * cell_height,
+            width: cell_width,
+            height: cell_height,
+        };
+        pieces.push(PieceLayout {
+            index,
+            file,
+            column,
+            row,
+            x: cell.x as i64 - padding,
+            y: cell.y as i64 - padding,
+            cell,
+            shape: cut.get_shape(index),
+            neighbours: grid.get_neighbours(index),
+        });
+    }
+
+    let layout = Layout {
+        picture: options.picture.to_string_lossy().to_string(),
+        width,
+        height,
+        columns,
+        rows,
+        seed: options.seed,
+        pieces,
+    };
+    let json = serde_json::to_string_pretty(&layout).map_err(|e| e.to_string())?;
+    let layout_path = options.out.join(LAYOUT_FILE);
+    fs::write(&layout_path, json)
+        .map_err(|e| format!("can not write {}: {e}", layout_path.display()))?;
+
+    println!(
+        "{} pieces ({columns} x {rows}, seed {}) written to {}",
+        grid.get_count(),
+        options.seed,
+        options.out.display()
+    );
+    Ok(())
+}
+
+fn save_png(piece: &image::RgbaImage, path: &Path) -> Result<(), String> {
+    piece
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| format!("can not write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn args(args: &[&str]) -> Result<Option<Options>, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    /// a small picture with a different color on every pixel, written to a folder of its own
+    fn picture(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jigsaw-cut-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("picture.png");
+        RgbaImage::from_fn(90, 60, |x, y| Rgba([x as u8 * 2, y as u8 * 4, 128, 255]))
+            .save(&path)
+            .unwrap();
+        path
+    }
+
+    fn options(picture: &Path, out: &str) -> Options {
+        Options {
+            picture: picture.to_path_buf(),
+            pieces: TotalPieces::P6,
+            seed: 42,
+            out: picture.with_file_name(out),
+        }
+    }
+
+    fn read(dir: &Path) -> Vec<(String, Vec<u8>)> {
+        let mut files: Vec<(String, Vec<u8>)> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                (name, fs::read(&path).unwrap())
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn arguments() {
+        let options = args(&["cat.jpg", "--pieces", "54", "--seed", "7"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.picture, PathBuf::from("cat.jpg"));
+        assert_eq!(options.pieces, TotalPieces::P54);
+        assert_eq!(options.seed, 7);
+        assert_eq!(options.out, PathBuf::from("cat-pieces"));
+
+        let options = args(&["--out", "here", "cat.jpg"]).unwrap().unwrap();
+        assert_eq!(options.pieces, TotalPieces::P24);
+        assert_eq!(options.out, PathBuf::from("here"));
+
+        assert!(args(&["--help"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn bad_arguments() {
+        assert!(args(&[]).is_err());
+        assert!(args(&["a.jpg", "b.jpg"]).is_err());
+        assert!(args(&["a.jpg", "--pieces", "7"]).is_err());
+        assert!(args(&["a.jpg", "--seed", "x"]).is_err());
+        assert!(args(&["a.jpg", "--colour"]).is_err());
+    }
+
+    #[test]
+    fn picture_smaller_than_the_grid() {
+        let picture = picture("small");
+        RgbaImage::new(3, 2).save(&picture).unwrap();
+        let options = Options {
+            pieces: TotalPieces::P24,
+            ..options(&picture, "pieces")
+        };
+
+        let error = cut(&options).unwrap_err();
+        assert!(error.contains("too small"), "{error}");
+        assert!(!options.out.exists());
+        fs::remove_dir_all(picture.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn same_input_same_files() {
+        let picture = picture("same");
+        let first = options(&picture, "first");
+        let second = options(&picture, "second");
+        cut(&first).unwrap();
+        cut(&second).unwrap();
+
+        let files = read(&first.out);
+        // 6 pieces and the layout
+        assert_eq!(files.len(), 7);
+        assert_eq!(files, read(&second.out));
+        fs::remove_dir_all(picture.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn layout_lists_every_piece_and_its_neighbours() {
+        let picture = picture("layout");
+        let options = options(&picture, "pieces");
+        cut(&options).unwrap();
+
+        let layout: serde_json::Value =
+            serde_json::from_slice(&fs::read(options.out.join(LAYOUT_FILE)).unwrap()).unwrap();
+        let columns = layout["columns"].as_u64().unwrap() as u32;
+        let rows = layout["rows"].as_u64().unwrap() as u32;
+        let grid = Grid::new(columns, rows, 90., 60.);
+        let pieces = layout["pieces"].as_array().unwrap();
+        assert_eq!(pieces.len(), grid.get_count());
+        for (index, piece) in pieces.iter().enumerate() {
+            assert_eq!(piece["index"].as_u64(), Some(index as u64));
+            assert!(options.out.join(piece["file"].as_str().unwrap()).exists());
+            let neighbours: Vec<usize> = piece["neighbours"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|neighbour| neighbour.as_u64().unwrap() as usize)
+                .collect();
+            assert_eq!(neighbours, grid.get_neighbours(index));
+        }
+        fs::remove_dir_all(picture.parent().unwrap()).unwrap();
+    }
+}
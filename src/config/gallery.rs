@@ -0,0 +1,273 @@
+use crate::config::OnConfigScreen;
+use crate::config::level::Levels;
+use crate::config::total_pieces::TotalPieces;
+use crate::stats::{Records, format_time};
+use crate::{BUTTON_DEFAULT_BACKGROUND, BUTTON_SELECTED_BACKGROUND, TEXT_COLOR};
+use bevy::asset::RenderAssetUsages;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::picking::hover::HoverMap;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, poll_once};
+use image::DynamicImage;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const THUMBNAIL_WIDTH: u32 = 180;
+const THUMBNAIL_HEIGHT: u32 = 120;
+const GALLERY_HEIGHT: f32 = 260.;
+const SCROLL_LINE_HEIGHT: f32 = 40.;
+const BADGE_COLOR: Color = Color::srgb(0., 150., 0.);
+const PLACEHOLDER_COLOR: Color = Color::srgba(0., 0., 0., 0.1);
+
+/// thumbnails by picture path: uploaded this session, none for a picture that can not be read,
+/// or still being made off the main thread
+#[derive(Resource, Default)]
+pub struct Thumbnails {
+    ready: HashMap<String, Option<Handle<Image>>>,
+    making: HashMap<String, Task<Result<Image, image::ImageError>>>,
+}
+
+/// empty frame shown until the thumbnail of this picture is made
+#[derive(Component)]
+pub struct ThumbnailPlaceholder(String);
+
+#[derive(Component)]
+pub struct Gallery;
+
+#[derive(Component)]
+struct GalleryItem(usize);
+
+/// cached thumbnail file, named after the picture path and modification time
+fn thumbnail_path(path: &Path) -> Option<PathBuf> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    modified.hash(&mut hasher);
+
+    dirs::cache_dir().map(|dir| {
+        dir.join("my_jigsaw_game")
+            .join("thumbnails")
+            .join(format!("{:016x}.png", hasher.finish()))
+    })
+}
+
+/// read the cached thumbnail, or shrink the picture and cache it
+fn load_thumbnail(path: &Path) -> Result<DynamicImage, image::ImageError> {
+    let cached = thumbnail_path(path);
+    if let Some(thumbnail) = cached.as_ref().and_then(|cached| image::open(cached).ok()) {
+        return Ok(thumbnail);
+    }
+
+    let thumbnail = image::open(path)?.thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+    if let Some(cached) = cached {
+        let saved = cached
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(image::ImageError::IoError)
+            .and_then(|_| thumbnail.save(&cached));
+        if let Err(e) = saved {
+            warn!("can not cache thumbnail {}: {e}", cached.display());
+        }
+    }
+    Ok(thumbnail)
+}
+
+/// the thumbnail when it is made, otherwise start making it unless it is on its way
+fn get_thumbnail(path: &str, thumbnails: &mut Thumbnails) -> Option<Option<Handle<Image>>> {
+    if let Some(handle) = thumbnails.ready.get(path) {
+        return Some(handle.clone());
+    }
+    thumbnails
+        .making
+        .entry(path.to_string())
+        .or_insert_with(|| {
+            let path = PathBuf::from(path);
+            AsyncComputeTaskPool::get().spawn(async move {
+                load_thumbnail(&path).map(|thumbnail| {
+                    Image::from_dynamic(thumbnail, true, RenderAssetUsages::RENDER_WORLD)
+                })
+            })
+        });
+    None
+}
+
+/// upload the thumbnails made since last frame and swap them in for their placeholders
+pub fn finish_thumbnails(
+    mut commands: Commands,
+    mut thumbnails: ResMut<Thumbnails>,
+    mut images: ResMut<Assets<Image>>,
+    placeholders: Query<(Entity, &ThumbnailPlaceholder)>,
+) {
+    let mut made = vec![];
+    thumbnails
+        .making
+        .retain(|path, task| match block_on(poll_once(task)) {
+            Some(thumbnail) => {
+                made.push((path.clone(), thumbnail));
+                false
+            }
+            None => true,
+        });
+
+    for (path, thumbnail) in made {
+        let handle = match thumbnail {
+            Ok(thumbnail) => Some(images.add(thumbnail)),
+            Err(e) => {
+                warn!("can not make thumbnail for {path}: {e}");
+                None
+            }
+        };
+        for (entity, _) in placeholders.iter().filter(|(_, waiting)| waiting.0 == path) {
+            let mut node = commands.entity(entity);
+            node.remove::<(ThumbnailPlaceholder, BackgroundColor)>();
+            if let Some(handle) = &handle {
+                node.insert(ImageNode::new(handle.clone()));
+            }
+        }
+        thumbnails.ready.insert(path, handle);
+    }
+}
+
+pub fn spawn_gallery(commands: &mut Commands) -> Entity {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(90.),
+                height: Val::Px(GALLERY_HEIGHT),
+                flex_direction: FlexDirection::Row,
+                flex_wrap: FlexWrap::Wrap,
+                justify_content: JustifyContent::Center,
+                overflow: Overflow::scroll_y(),
+                ..default()
+            },
+            Gallery,
+            OnConfigScreen,
+        ))
+        .id()
+}
+
+/// rebuild the gallery items, on screen setup and whenever levels change
+pub fn render_gallery(
+    mut commands: Commands,
+    gallery: Query<Entity, With<Gallery>>,
+    level: Res<Levels>,
+    records: Res<Records>,
+    mut thumbnails: ResMut<Thumbnails>,
+) {
+    let Ok(gallery) = gallery.single() else {
+        return;
+    };
+    commands.entity(gallery).despawn_related::<Children>();
+
+    for (index, item) in level.get_all().iter().enumerate() {
+        let background = if index == level.get_current() {
+            BUTTON_SELECTED_BACKGROUND
+        } else {
+            BUTTON_DEFAULT_BACKGROUND
+        };
+
+        let best = records.get_best(item.get_path());
+        let badge = if best.is_empty() { "" } else { "solved" };
+        let best_times = best
+            .iter()
+            .map(|best| {
+                let turned = if best.rotation { " turned" } else { "" };
+                format!("{}{turned}: {}", best.pieces, format_time(best.seconds))
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+
+        // the width follows the picture shape
+        let mut thumbnail = commands.spawn(Node {
+            height: Val::Px(THUMBNAIL_HEIGHT as f32),
+            min_width: Val::Px(THUMBNAIL_HEIGHT as f32),
+            ..default()
+        });
+        match get_thumbnail(item.get_path(), &mut thumbnails) {
+            Some(Some(image)) => {
+                thumbnail.insert(ImageNode::new(image));
+            }
+            // a picture that can not be read keeps an empty frame
+            Some(None) => {}
+            None => {
+                thumbnail.insert((
+                    BackgroundColor(PLACEHOLDER_COLOR),
+                    ThumbnailPlaceholder(item.get_path().to_string()),
+                ));
+            }
+        }
+        let thumbnail = thumbnail.id();
+
+        let child = commands
+            .spawn((
+                Button,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    margin: UiRect::all(Val::Px(10.0)),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    ..default()
+                },
+                BackgroundColor(background),
+                GalleryItem(index),
+                children![
+                    (Text::new(item.get_title()), TextColor(TEXT_COLOR)),
+                    (Text::new(badge), TextColor(BADGE_COLOR)),
+                    (
+                        Text::new(best_times),
+                        TextFont::from_font_size(14.),
+                        TextColor(TEXT_COLOR)
+                    ),
+                ],
+            ))
+            .observe(gallery_item_click)
+            .id();
+        commands.entity(child).insert_children(0, &[thumbnail]);
+        commands.entity(gallery).add_child(child);
+    }
+}
+
+fn gallery_item_click(
+    click: Trigger<Pointer<Click>>,
+    items: Query<&GalleryItem>,
+    mut level: ResMut<Levels>,
+    mut total_pieces: ResMut<TotalPieces>,
+) {
+    if let Ok(item) = items.get(click.target) {
+        level.select(item.0);
+        if let Some(current) = level.current_level() {
+            *total_pieces = current.get_pieces();
+        }
+    }
+}
+
+/// mouse wheel scrolls the gallery under the cursor
+pub fn scroll_gallery(
+    mut wheel: EventReader<MouseWheel>,
+    hover_map: Res<HoverMap>,
+    parents: Query<&ChildOf>,
+    mut galleries: Query<&mut ScrollPosition, With<Gallery>>,
+) {
+    for event in wheel.read() {
+        let delta = match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for pointer_map in hover_map.values() {
+            for entity in pointer_map.keys() {
+                // the hovered entity may be any item inside the gallery
+                for ancestor in std::iter::once(*entity).chain(parents.iter_ancestors(*entity)) {
+                    if let Ok(mut scroll) = galleries.get_mut(ancestor) {
+                        scroll.offset_y -= delta;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
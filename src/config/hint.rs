@@ -0,0 +1,32 @@
+use bevy::prelude::Resource;
+use std::fmt::Display;
+
+const LIMITS: [u32; 4] = [0, 3, 10, 30];
+
+/// hints allowed per puzzle, 0 turns them off
+#[derive(Resource, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct HintLimit(pub u32);
+
+impl Default for HintLimit {
+    fn default() -> Self {
+        HintLimit(3)
+    }
+}
+
+impl HintLimit {
+    /// the next limit offered on the config screen
+    pub fn next(&self) -> HintLimit {
+        let index = LIMITS.iter().position(|limit| *limit == self.0);
+        HintLimit(LIMITS[index.map_or(0, |index| (index + 1) % LIMITS.len())])
+    }
+}
+
+impl Display for HintLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 == 0 {
+            write!(f, "hints off")
+        } else {
+            write!(f, "{} hints", self.0)
+        }
+    }
+}
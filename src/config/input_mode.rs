@@ -0,0 +1,21 @@
+use bevy::prelude::Resource;
+use std::fmt::Display;
+
+/// how pieces are taken and put down
+#[derive(Resource, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum InputMode {
+    /// click to pick up, click again to drop
+    #[default]
+    Click,
+    /// press to pick up, drag, release to drop
+    Drag,
+}
+
+impl Display for InputMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputMode::Click => write!(f, "click to move"),
+            InputMode::Drag => write!(f, "drag to move"),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use crate::GameState;
+use crate::config::level::Levels;
+use crate::config::resume_game;
+use crate::config::rotation::RotationMode;
+use crate::config::seed::SeedEntry;
+use crate::config::total_pieces::TotalPieces;
+use crate::play::save::{SaveFile, SaveGame};
+use bevy::prelude::*;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "usage: my_jigsaw_game [options]
+
+  --image <path>     play this picture
+  --pieces <count>   cut it in about this many pieces
+  --seed <number>    cut, scatter and turn the pieces with this seed
+  --rotation         start with turned pieces
+  --fullscreen       open in full screen
+  --resume <file>    continue the game saved in this file, progress is saved back to it
+  --help             show this message
+
+with --image, --pieces, --seed or --resume the game starts right away, skipping the config screen";
+
+/// what the command line asks for, applied once the levels are loaded
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct Launch {
+    pub image: Option<PathBuf>,
+    pub pieces: Option<TotalPieces>,
+    pub seed: Option<u64>,
+    pub rotation: bool,
+    pub fullscreen: bool,
+    pub resume: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Launch {
+    /// read the arguments following the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut launch = Launch::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--image" => launch.image = Some(PathBuf::from(value(&arg, args.next())?)),
+                "--pieces" => launch.pieces = Some(TotalPieces::parse(&value(&arg, args.next())?)?),
+                "--seed" => {
+                    let seed = value(&arg, args.next())?;
+                    launch.seed = Some(
+                        seed.parse()
+                            .map_err(|_| format!("--seed takes a whole number, not {seed}"))?,
+                    );
+                }
+                "--rotation" => launch.rotation = true,
+                "--fullscreen" => launch.fullscreen = true,
+                "--resume" => launch.resume = Some(PathBuf::from(value(&arg, args.next())?)),
+                "--help" | "-h" => launch.help = true,
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+        Ok(launch)
+    }
+
+    /// whether the game goes straight to the puzzle
+    pub fn skips_config(&self) -> bool {
+        self.image.is_some()
+            || self.pieces.is_some()
+            || self.seed.is_some()
+            || self.resume.is_some()
+    }
+}
+
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{flag} needs a value"))
+}
+
+/// set up the levels and settings from the command line, and start the puzzle when asked;
+/// a picture or save that can not be read is reported on the config screen instead
+pub(super) fn apply_launch(
+    mut commands: Commands,
+    launch: Res<Launch>,
+    mut level: ResMut<Levels>,
+    mut total_pieces: ResMut<TotalPieces>,
+    mut rotation_mode: ResMut<RotationMode>,
+    mut seed_entry: ResMut<SeedEntry>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    if let Some(path) = &launch.resume {
+        commands.insert_resource(SaveFile(path.clone()));
+        match SaveGame::load(path) {
+            Ok(save) => {
+                resume_game(
+                    &mut commands,
+                    save,
+                    &mut level,
+                    &mut total_pieces,
+                    &mut rotation_mode,
+                    &mut seed_entry,
+                );
+                state.set(GameState::Loading);
+            }
+            Err(e) => level.report(format!("can not resume {}: {e}", path.display())),
+        }
+        return;
+    }
+
+    if let Some(path) = &launch.image {
+        match level.add_picture(path) {
+            Ok(index) => {
+                level.select(index);
+                if let Some(picked) = level.current_level() {
+                    *total_pieces = picked.get_pieces();
+                }
+            }
+            Err(e) => {
+                level.report(e);
+                return;
+            }
+        }
+    }
+    if let Some(pieces) = launch.pieces {
+        *total_pieces = pieces;
+    }
+    if let Some(seed) = launch.seed {
+        seed_entry.text = seed.to_string();
+    }
+    if launch.rotation {
+        rotation_mode.0 = true;
+    }
+    if launch.skips_config() && level.current_level().is_some() {
+        state.set(GameState::Loading);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Launch, String> {
+        Launch::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_stay_on_the_config_screen() {
+        let launch = parse(&[]).unwrap();
+        assert_eq!(launch, Launch::default());
+        assert!(!launch.skips_config());
+    }
+
+    #[test]
+    fn every_flag() {
+        let launch = parse(&[
+            "--image",
+            "cat.jpg",
+            "--pieces",
+            "54",
+            "--seed",
+            "42",
+            "--rotation",
+            "--fullscreen",
+        ])
+        .unwrap();
+        assert_eq!(launch.image, Some(PathBuf::from("cat.jpg")));
+        assert_eq!(launch.pieces, Some(TotalPieces::P54));
+        assert_eq!(launch.seed, Some(42));
+        assert!(launch.rotation && launch.fullscreen);
+        assert!(launch.skips_config());
+    }
+
+    #[test]
+    fn settings_alone_stay_on_the_config_screen() {
+        let launch = parse(&["--rotation", "--fullscreen"]).unwrap();
+        assert!(!launch.skips_config());
+    }
+
+    #[test]
+    fn bad_arguments() {
+        assert!(parse(&["--pieces", "55"]).is_err());
+        assert!(parse(&["--seed", "-1"]).is_err());
+        assert!(parse(&["--image"]).is_err());
+        assert!(parse(&["--colour"]).is_err());
+    }
+}
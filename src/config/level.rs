@@ -1,50 +1,259 @@
+use crate::config::total_pieces::TotalPieces;
 use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-const TOTAL_LEVEL: usize = 2;
+const LEVELS_PATH: &str = "assets/levels.ron";
 
+const PICTURE_EXTENSIONS: [&str; 4] = ["jpg", "jpeg", "png", "webp"];
+const MIN_PICTURE_SIDE: u32 = 200;
+const MY_PICTURES_CATEGORY: &str = "my pictures";
+
+/// folder scanned for the player's own pictures, `JIGSAW_PICTURES` overrides ~/Pictures/jigsaw
+fn my_pictures_dir() -> Option<PathBuf> {
+    std::env::var_os("JIGSAW_PICTURES")
+        .map(PathBuf::from)
+        .or_else(|| dirs::picture_dir().map(|dir| dir.join("jigsaw")))
+}
+
+/// make sure the picture can be cut before it becomes a level
+fn check_picture(path: &Path) -> Result<(), String> {
+    let (width, height) = image::image_dimensions(path)
+        .map_err(|e| format!("can not read {}: {e}", path.display()))?;
+    if width.min(height) < MIN_PICTURE_SIDE {
+        return Err(format!(
+            "{} is too small ({width}x{height}), pictures need at least {MIN_PICTURE_SIDE} pixels on each side",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize, Debug, Clone)]
 pub struct Level {
     path: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    category: String,
+    #[serde(default = "default_pieces")]
+    pieces: TotalPieces,
+}
+
+fn default_pieces() -> TotalPieces {
+    TotalPieces::P24
 }
 
 impl Level {
     pub fn get_path(&self) -> &str {
         &self.path
     }
+
+    pub fn get_title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn get_author(&self) -> &str {
+        &self.author
+    }
+
+    pub fn get_category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn get_pieces(&self) -> TotalPieces {
+        self.pieces
+    }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct Levels {
     all: Vec<Level>,
     current: usize,
+    /// manifest problems, shown on the config screen
+    errors: Vec<String>,
 }
 
-impl Default for Levels {
-    fn default() -> Self {
-        Levels {
-            all: vec![
-                Level {
-                    path: "assets/resources/1.jpg".to_string(),
-                },
-                Level {
-                    path: "assets/resources/2.jpg".to_string(),
-                },
-            ],
-            current: 0,
+impl Levels {
+    /// read the manifest, entries whose picture can not be read are skipped and reported
+    pub fn load() -> Self {
+        let mut levels = Levels::default();
+
+        let manifest = match fs::read_to_string(LEVELS_PATH) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                levels
+                    .errors
+                    .push(format!("can not read {LEVELS_PATH}: {e}"));
+                return levels;
+            }
+        };
+        let entries: Vec<Level> = match ron::from_str(&manifest) {
+            Ok(entries) => entries,
+            Err(e) => {
+                levels
+                    .errors
+                    .push(format!("can not parse {LEVELS_PATH}: {e}"));
+                return levels;
+            }
+        };
+
+        for level in entries {
+            match check_picture(Path::new(&level.path)) {
+                Ok(_) => levels.all.push(level),
+                Err(e) => levels.errors.push(format!("skip level: {e}")),
+            }
         }
+
+        if let Some(dir) = my_pictures_dir() {
+            levels.scan_my_pictures(&dir);
+        }
+        levels
     }
-}
 
-impl Levels {
-    pub fn current_level(&self) -> &Level {
-        self.all.get(self.current).unwrap()
+    fn scan_my_pictures(&mut self, dir: &Path) {
+        // the folder is optional
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|extension| extension.to_str())
+                    .is_some_and(|extension| {
+                        PICTURE_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+                    })
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            if let Err(e) = self.add_picture(&path) {
+                self.errors.push(e);
+            }
+        }
+    }
+
+    /// add one of the player's pictures, returns its index
+    pub fn add_picture(&mut self, path: &Path) -> Result<usize, String> {
+        let path_string = path.to_string_lossy().to_string();
+        if let Some(index) = self.all.iter().position(|level| level.path == path_string) {
+            return Ok(index);
+        }
+        check_picture(path)?;
+
+        self.all.push(Level {
+            path: path_string,
+            title: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            author: String::new(),
+            category: MY_PICTURES_CATEGORY.to_string(),
+            pieces: default_pieces(),
+        });
+        Ok(self.all.len() - 1)
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.all.len() {
+            self.current = index;
+        }
+    }
+
+    /// keep a problem to show on the config screen
+    pub fn report(&mut self, error: String) {
+        warn!("{error}");
+        self.errors.push(error);
+    }
+
+    pub fn get_all(&self) -> &[Level] {
+        &self.all
+    }
+
+    pub fn get_current(&self) -> usize {
+        self.current
+    }
+
+    pub fn current_level(&self) -> Option<&Level> {
+        self.all.get(self.current)
+    }
+
+    pub fn get_errors(&self) -> &[String] {
+        &self.errors
     }
 
     pub fn next_level(&mut self) {
+        if self.all.is_empty() {
+            return;
+        }
         self.current += 1;
-        self.current %= TOTAL_LEVEL;
+        self.current %= self.all.len();
     }
 
-    pub fn random_level(&mut self) {
-        self.current = rand::random::<usize>() % TOTAL_LEVEL;
+    /// select the level showing `path`, adding it when it is not known
+    pub fn select_path(&mut self, path: &str) {
+        match self.all.iter().position(|level| level.path == path) {
+            Some(index) => self.current = index,
+            None => {
+                self.all.push(Level {
+                    path: path.to_string(),
+                    title: String::new(),
+                    author: String::new(),
+                    category: String::new(),
+                    pieces: default_pieces(),
+                });
+                self.current = self.all.len() - 1;
+            }
+        }
+    }
+
+    /// select the level of the daily puzzle, only levels of the manifest are shared by everyone;
+    /// returns false without any
+    pub fn select_daily(&mut self, seed: u64) -> bool {
+        let shared: Vec<usize> = (0..self.all.len())
+            .filter(|index| self.all[*index].category != MY_PICTURES_CATEGORY)
+            .collect();
+        if shared.is_empty() {
+            return false;
+        }
+        self.current = shared[(seed % shared.len() as u64) as usize];
+        true
+    }
+
+    /// select a level drawn from `rng`
+    pub fn random_level(&mut self, rng: &mut impl Rng) {
+        if self.all.is_empty() {
+            return;
+        }
+        self.current = rng.gen_range(0, self.all.len());
+    }
+}
+
+/// pictures dropped on the window become a level right away
+pub fn drop_picture(mut events: EventReader<FileDragAndDrop>, mut levels: ResMut<Levels>) {
+    for event in events.read() {
+        if let FileDragAndDrop::DroppedFile { path_buf, .. } = event {
+            match levels.add_picture(path_buf) {
+                Ok(index) => levels.select(index),
+                Err(e) => levels.report(e),
+            }
+        }
+    }
+}
+
+pub fn load_levels(mut commands: Commands, mut total_pieces: ResMut<TotalPieces>) {
+    let levels = Levels::load();
+    for error in levels.get_errors() {
+        warn!("{error}");
+    }
+    if let Some(level) = levels.current_level() {
+        *total_pieces = level.get_pieces();
     }
+    commands.insert_resource(levels);
 }
@@ -1,22 +1,95 @@
-use crate::config::level::Levels;
+use crate::config::gallery::{
+    Thumbnails, finish_thumbnails, render_gallery, scroll_gallery, spawn_gallery,
+};
+use crate::config::hint::HintLimit;
+use crate::config::input_mode::InputMode;
+use crate::config::launch::{Launch, apply_launch};
+use crate::config::level::{Levels, drop_picture, load_levels};
+use crate::config::preview::{GhostOpacity, PreviewModes};
+use crate::config::rotation::RotationMode;
+use crate::config::seed::{MAX_SEED_DIGITS, SeedEntry, daily_pieces, daily_seed, today};
 use crate::config::total_pieces::TotalPieces;
+use crate::config::undo::UndoDepth;
+use crate::play::save::{RestoreGame, SaveFile, SaveGame};
 use crate::{
     BUTTON_DEFAULT_BACKGROUND, BUTTON_SELECTED_BACKGROUND, GameState, TEXT_COLOR, despawn_screen,
 };
+use bevy::input::keyboard::{Key, KeyboardInput};
 use bevy::prelude::*;
 use strum::IntoEnumIterator;
 
+mod gallery;
+pub mod hint;
+pub mod input_mode;
+pub mod launch;
 pub(crate) mod level;
+pub mod preview;
+pub mod rotation;
+pub mod seed;
 pub mod total_pieces;
+pub mod undo;
+
+const ERROR_COLOR: Color = Color::srgb(255., 0., 0.);
 
 pub fn config_plugin(app: &mut App) {
-    app.add_systems(OnEnter(GameState::Config), setup_config)
-        .add_systems(OnExit(GameState::Config), despawn_screen::<OnConfigScreen>)
-        .add_systems(
-            Update,
-            render_piece_color.run_if(resource_changed::<TotalPieces>),
-        )
-        .insert_resource(TotalPieces::P24);
+    app.add_systems(
+        Startup,
+        (load_levels, apply_launch.run_if(resource_exists::<Launch>)).chain(),
+    )
+    .add_systems(
+        OnEnter(GameState::Config),
+        (setup_config, render_gallery).chain(),
+    )
+    .add_systems(OnExit(GameState::Config), despawn_screen::<OnConfigScreen>)
+    .add_systems(Update, drop_picture.run_if(in_state(GameState::Config)))
+    .add_systems(
+        Update,
+        (render_level, render_gallery)
+            .run_if(in_state(GameState::Config).and(resource_changed::<Levels>)),
+    )
+    .add_systems(Update, scroll_gallery.run_if(in_state(GameState::Config)))
+    .add_systems(
+        Update,
+        finish_thumbnails
+            .after(render_gallery)
+            .run_if(in_state(GameState::Config)),
+    )
+    .init_resource::<Thumbnails>()
+    .add_systems(
+        Update,
+        render_piece_color.run_if(resource_changed::<TotalPieces>),
+    )
+    .add_systems(
+        Update,
+        render_rotation_color.run_if(resource_changed::<RotationMode>),
+    )
+    .add_systems(
+        Update,
+        render_input_mode.run_if(resource_changed::<InputMode>),
+    )
+    .add_systems(
+        Update,
+        render_undo_depth.run_if(resource_changed::<UndoDepth>),
+    )
+    .add_systems(
+        Update,
+        render_hint_limit.run_if(resource_changed::<HintLimit>),
+    )
+    .add_systems(Update, type_seed.run_if(in_state(GameState::Config)))
+    .add_systems(Update, render_seed.run_if(resource_changed::<SeedEntry>))
+    .add_systems(
+        Update,
+        render_preview_mode
+            .run_if(resource_changed::<PreviewModes>.or(resource_changed::<TotalPieces>)),
+    )
+    .insert_resource(TotalPieces::P24)
+    .init_resource::<RotationMode>()
+    .init_resource::<InputMode>()
+    .init_resource::<UndoDepth>()
+    .init_resource::<HintLimit>()
+    .init_resource::<PreviewModes>()
+    .init_resource::<GhostOpacity>()
+    .init_resource::<SeedEntry>();
 }
 
 #[derive(Component)]
@@ -24,14 +97,80 @@ struct PieceButton {
     total_piece: TotalPieces,
 }
 
-fn start_game(_: Trigger<Pointer<Click>>, mut state: ResMut<NextState<GameState>>) {
-    state.set(GameState::Play);
+#[derive(Component)]
+struct RotationButton;
+
+#[derive(Component)]
+struct InputModeButton;
+
+#[derive(Component)]
+struct UndoButton;
+
+#[derive(Component)]
+struct HintButton;
+
+#[derive(Component)]
+struct PreviewButton;
+
+#[derive(Component)]
+struct SeedButton;
+
+#[derive(Component)]
+struct LevelLabel;
+
+#[derive(Component)]
+struct ErrorLabel;
+
+fn start_game(
+    _: Trigger<Pointer<Click>>,
+    level: Res<Levels>,
+    mut seed_entry: ResMut<SeedEntry>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    if level.current_level().is_none() {
+        warn!("no level to play");
+        return;
+    }
+    seed_entry.typing = false;
+    seed_entry.daily = false;
+    state.set(GameState::Loading);
+}
+
+/// today's puzzle: level, piece count and seed all come from the date, see `SeedEntry::rotates`
+fn start_daily(
+    _: Trigger<Pointer<Click>>,
+    mut level: ResMut<Levels>,
+    mut total_pieces: ResMut<TotalPieces>,
+    mut seed_entry: ResMut<SeedEntry>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    let seed = daily_seed(today());
+    if !level.select_daily(seed) {
+        level.report("the daily puzzle needs a level from the manifest".to_string());
+        return;
+    }
+    *total_pieces = daily_pieces(seed);
+    seed_entry.typing = false;
+    seed_entry.daily = true;
+    state.set(GameState::Loading);
 }
 
 #[derive(Component)]
-struct OnConfigScreen;
+pub(crate) struct OnConfigScreen;
 
-fn setup_config(mut commands: Commands) {
+#[allow(clippy::too_many_arguments)]
+fn setup_config(
+    mut commands: Commands,
+    rotation_mode: Res<RotationMode>,
+    input_mode: Res<InputMode>,
+    undo_depth: Res<UndoDepth>,
+    hint_limit: Res<HintLimit>,
+    seed_entry: Res<SeedEntry>,
+    preview_modes: Res<PreviewModes>,
+    total_pieces: Res<TotalPieces>,
+    save_file: Res<SaveFile>,
+    level: Res<Levels>,
+) {
     let button_node = Node {
         width: Val::Px(300.0),
         height: Val::Px(65.0),
@@ -44,6 +183,7 @@ fn setup_config(mut commands: Commands) {
     let parent = commands
         .spawn((
             Node {
+                width: Val::Percent(100.),
                 flex_direction: FlexDirection::Column,
                 align_items: AlignItems::Center,
                 ..default()
@@ -52,10 +192,21 @@ fn setup_config(mut commands: Commands) {
         ))
         .id();
 
+    let gallery = spawn_gallery(&mut commands);
+    commands.entity(parent).add_child(gallery);
+
+    let piece_button_node = Node {
+        width: Val::Px(120.0),
+        margin: UiRect::all(Val::Px(10.0)),
+        ..button_node.clone()
+    };
+
     let piece_parent = commands
         .spawn((
             Node {
                 flex_direction: FlexDirection::Row,
+                flex_wrap: FlexWrap::Wrap,
+                justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 ..default()
             },
@@ -63,13 +214,36 @@ fn setup_config(mut commands: Commands) {
         ))
         .id();
 
+    let level_label = commands
+        .spawn((
+            Text::new(level_text(&level)),
+            Node {
+                margin: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            LevelLabel,
+            OnConfigScreen,
+        ))
+        .id();
+    commands.entity(parent).add_child(level_label);
+
+    let error_label = commands
+        .spawn((
+            Text::new(level.get_errors().join("\n")),
+            TextColor(ERROR_COLOR),
+            ErrorLabel,
+            OnConfigScreen,
+        ))
+        .id();
+    commands.entity(parent).add_child(error_label);
+
     commands.entity(parent).add_child(piece_parent);
 
     for total_piece in TotalPieces::iter() {
         let child = commands
             .spawn((
                 Button,
-                button_node.clone(),
+                piece_button_node.clone(),
                 PieceButton { total_piece },
                 BackgroundColor(BUTTON_DEFAULT_BACKGROUND),
                 children![(Text::new(total_piece.to_string()), TextColor(TEXT_COLOR),)],
@@ -80,6 +254,87 @@ fn setup_config(mut commands: Commands) {
         commands.entity(piece_parent).add_child(child);
     }
 
+    let rotation = commands
+        .spawn((
+            Button,
+            button_node.clone(),
+            RotationButton,
+            BackgroundColor(rotation_color(&rotation_mode)),
+            children![(Text::new(rotation_mode.to_string()), TextColor(TEXT_COLOR),)],
+            OnConfigScreen,
+        ))
+        .observe(rotation_button_click)
+        .id();
+    commands.entity(piece_parent).add_child(rotation);
+
+    let input = commands
+        .spawn((
+            Button,
+            button_node.clone(),
+            InputModeButton,
+            BackgroundColor(BUTTON_DEFAULT_BACKGROUND),
+            children![(Text::new(input_mode.to_string()), TextColor(TEXT_COLOR),)],
+            OnConfigScreen,
+        ))
+        .observe(input_mode_button_click)
+        .id();
+    commands.entity(piece_parent).add_child(input);
+
+    let undo = commands
+        .spawn((
+            Button,
+            button_node.clone(),
+            UndoButton,
+            BackgroundColor(BUTTON_DEFAULT_BACKGROUND),
+            children![(Text::new(undo_depth.to_string()), TextColor(TEXT_COLOR),)],
+            OnConfigScreen,
+        ))
+        .observe(undo_button_click)
+        .id();
+    commands.entity(piece_parent).add_child(undo);
+
+    let hint = commands
+        .spawn((
+            Button,
+            button_node.clone(),
+            HintButton,
+            BackgroundColor(BUTTON_DEFAULT_BACKGROUND),
+            children![(Text::new(hint_limit.to_string()), TextColor(TEXT_COLOR),)],
+            OnConfigScreen,
+        ))
+        .observe(hint_button_click)
+        .id();
+    commands.entity(piece_parent).add_child(hint);
+
+    let preview = commands
+        .spawn((
+            Button,
+            button_node.clone(),
+            PreviewButton,
+            BackgroundColor(BUTTON_DEFAULT_BACKGROUND),
+            children![(
+                Text::new(preview_modes.get(&total_pieces).to_string()),
+                TextColor(TEXT_COLOR),
+            )],
+            OnConfigScreen,
+        ))
+        .observe(preview_button_click)
+        .id();
+    commands.entity(piece_parent).add_child(preview);
+
+    let seed = commands
+        .spawn((
+            Button,
+            button_node.clone(),
+            SeedButton,
+            BackgroundColor(BUTTON_DEFAULT_BACKGROUND),
+            children![(Text::new(seed_entry.to_string()), TextColor(TEXT_COLOR),)],
+            OnConfigScreen,
+        ))
+        .observe(seed_button_click)
+        .id();
+    commands.entity(piece_parent).add_child(seed);
+
     let start_game = commands
         .spawn((
             Button,
@@ -90,7 +345,91 @@ fn setup_config(mut commands: Commands) {
         .observe(start_game)
         .id();
     commands.entity(parent).add_child(start_game);
-    commands.insert_resource(Levels::default())
+
+    let daily = commands
+        .spawn((
+            Button,
+            button_node.clone(),
+            children![Text::new("daily puzzle")],
+            OnConfigScreen,
+        ))
+        .observe(start_daily)
+        .id();
+    commands.entity(parent).add_child(daily);
+
+    let stats = commands
+        .spawn((
+            Button,
+            button_node.clone(),
+            children![Text::new("stats")],
+            OnConfigScreen,
+        ))
+        .observe(show_stats)
+        .id();
+    commands.entity(parent).add_child(stats);
+
+    if SaveGame::exists(&save_file.0) {
+        let continue_game = commands
+            .spawn((
+                Button,
+                button_node.clone(),
+                children![Text::new("continue")],
+                OnConfigScreen,
+            ))
+            .observe(continue_game)
+            .id();
+        commands.entity(parent).add_child(continue_game);
+    }
+}
+
+fn show_stats(_: Trigger<Pointer<Click>>, mut state: ResMut<NextState<GameState>>) {
+    state.set(GameState::Stats);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn continue_game(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut level: ResMut<Levels>,
+    mut total_pieces: ResMut<TotalPieces>,
+    mut rotation_mode: ResMut<RotationMode>,
+    mut seed_entry: ResMut<SeedEntry>,
+    save_file: Res<SaveFile>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    let save = match SaveGame::load(&save_file.0) {
+        Ok(save) => save,
+        Err(e) => {
+            warn!("can not load saved game: {e}");
+            return;
+        }
+    };
+
+    resume_game(
+        &mut commands,
+        save,
+        &mut level,
+        &mut total_pieces,
+        &mut rotation_mode,
+        &mut seed_entry,
+    );
+    state.set(GameState::Loading);
+}
+
+/// select the level and settings of a saved game, loading then puts its pieces back
+fn resume_game(
+    commands: &mut Commands,
+    save: SaveGame,
+    level: &mut Levels,
+    total_pieces: &mut TotalPieces,
+    rotation_mode: &mut RotationMode,
+    seed_entry: &mut SeedEntry,
+) {
+    level.select_path(&save.level);
+    *total_pieces = save.total_pieces;
+    rotation_mode.0 = save.rotation;
+    seed_entry.daily = false;
+    commands.insert_resource(RestoreGame(save));
 }
 
 fn total_piece_button_click(
@@ -116,3 +455,184 @@ fn render_piece_color(
         }
     }
 }
+
+fn level_text(level: &Levels) -> String {
+    match level.current_level() {
+        Some(level) => format!(
+            "{} by {} ({})",
+            level.get_title(),
+            level.get_author(),
+            level.get_category()
+        ),
+        None => "no level available".to_string(),
+    }
+}
+
+fn render_level(
+    level: Res<Levels>,
+    mut level_labels: Query<&mut Text, (With<LevelLabel>, Without<ErrorLabel>)>,
+    mut error_labels: Query<&mut Text, With<ErrorLabel>>,
+) {
+    for mut text in level_labels.iter_mut() {
+        *text = Text::new(level_text(&level));
+    }
+    for mut text in error_labels.iter_mut() {
+        *text = Text::new(level.get_errors().join("\n"));
+    }
+}
+
+fn rotation_button_click(_: Trigger<Pointer<Click>>, mut rotation_mode: ResMut<RotationMode>) {
+    rotation_mode.0 = !rotation_mode.0;
+}
+
+fn render_rotation_color(
+    rotation_mode: Res<RotationMode>,
+    query: Query<(&mut BackgroundColor, &Children), With<RotationButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    for (mut background, children) in query {
+        *background = BackgroundColor(rotation_color(&rotation_mode));
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                *text = Text::new(rotation_mode.to_string());
+            }
+        }
+    }
+}
+
+fn input_mode_button_click(_: Trigger<Pointer<Click>>, mut input_mode: ResMut<InputMode>) {
+    *input_mode = match *input_mode {
+        InputMode::Click => InputMode::Drag,
+        InputMode::Drag => InputMode::Click,
+    };
+}
+
+fn render_input_mode(
+    input_mode: Res<InputMode>,
+    query: Query<&Children, With<InputModeButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    for children in query {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                *text = Text::new(input_mode.to_string());
+            }
+        }
+    }
+}
+
+fn undo_button_click(_: Trigger<Pointer<Click>>, mut undo_depth: ResMut<UndoDepth>) {
+    *undo_depth = undo_depth.next();
+}
+
+fn render_undo_depth(
+    undo_depth: Res<UndoDepth>,
+    query: Query<&Children, With<UndoButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    for children in query {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                *text = Text::new(undo_depth.to_string());
+            }
+        }
+    }
+}
+
+fn hint_button_click(_: Trigger<Pointer<Click>>, mut hint_limit: ResMut<HintLimit>) {
+    *hint_limit = hint_limit.next();
+}
+
+fn render_hint_limit(
+    hint_limit: Res<HintLimit>,
+    query: Query<&Children, With<HintButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    for children in query {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                *text = Text::new(hint_limit.to_string());
+            }
+        }
+    }
+}
+
+/// the preview is kept per number of pieces, the button changes the one currently chosen
+fn preview_button_click(
+    _: Trigger<Pointer<Click>>,
+    total_pieces: Res<TotalPieces>,
+    mut preview_modes: ResMut<PreviewModes>,
+) {
+    let mode = preview_modes.get(&total_pieces).next();
+    preview_modes.set(*total_pieces, mode);
+}
+
+fn render_preview_mode(
+    preview_modes: Res<PreviewModes>,
+    total_pieces: Res<TotalPieces>,
+    query: Query<&Children, With<PreviewButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    for children in query {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                *text = Text::new(preview_modes.get(&total_pieces).to_string());
+            }
+        }
+    }
+}
+
+/// start or stop typing a seed, the daily seed is dropped
+fn seed_button_click(_: Trigger<Pointer<Click>>, mut seed_entry: ResMut<SeedEntry>) {
+    seed_entry.typing = !seed_entry.typing;
+    seed_entry.daily = false;
+}
+
+/// digits typed while the seed field is active, enter or escape ends typing
+fn type_seed(mut keyboard: EventReader<KeyboardInput>, mut seed_entry: ResMut<SeedEntry>) {
+    if !seed_entry.typing {
+        keyboard.clear();
+        return;
+    }
+    for event in keyboard.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(text) => {
+                for digit in text.chars().filter(char::is_ascii_digit) {
+                    if seed_entry.text.len() < MAX_SEED_DIGITS {
+                        seed_entry.text.push(digit);
+                    }
+                }
+            }
+            Key::Backspace => {
+                seed_entry.text.pop();
+            }
+            Key::Enter | Key::Escape => seed_entry.typing = false,
+            _ => {}
+        }
+    }
+}
+
+fn render_seed(
+    seed_entry: Res<SeedEntry>,
+    query: Query<&Children, With<SeedButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    for children in query {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                *text = Text::new(seed_entry.to_string());
+            }
+        }
+    }
+}
+
+fn rotation_color(rotation_mode: &RotationMode) -> Color {
+    if rotation_mode.0 {
+        BUTTON_SELECTED_BACKGROUND
+    } else {
+        BUTTON_DEFAULT_BACKGROUND
+    }
+}
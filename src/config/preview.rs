@@ -0,0 +1,77 @@
+use crate::config::total_pieces::TotalPieces;
+use bevy::prelude::Resource;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// reference pictures shown while playing
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum PreviewMode {
+    /// a faint picture under the board and a small one in a corner
+    Both,
+    /// a faint picture under the board
+    Ghost,
+    /// a small picture in a corner, P shows or hides it
+    #[default]
+    Window,
+    /// challenge mode, nothing to look at
+    Off,
+}
+
+impl PreviewMode {
+    pub fn shows_ghost(&self) -> bool {
+        matches!(self, PreviewMode::Both | PreviewMode::Ghost)
+    }
+
+    pub fn shows_window(&self) -> bool {
+        matches!(self, PreviewMode::Both | PreviewMode::Window)
+    }
+
+    /// the next mode offered on the config screen
+    pub fn next(&self) -> PreviewMode {
+        match self {
+            PreviewMode::Both => PreviewMode::Ghost,
+            PreviewMode::Ghost => PreviewMode::Window,
+            PreviewMode::Window => PreviewMode::Off,
+            PreviewMode::Off => PreviewMode::Both,
+        }
+    }
+}
+
+impl Display for PreviewMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreviewMode::Both => write!(f, "ghost and preview"),
+            PreviewMode::Ghost => write!(f, "ghost only"),
+            PreviewMode::Window => write!(f, "preview only"),
+            PreviewMode::Off => write!(f, "no preview"),
+        }
+    }
+}
+
+/// preview chosen for each number of pieces, big puzzles get the ghost too unless changed
+#[derive(Resource, Debug, Default)]
+pub struct PreviewModes(HashMap<TotalPieces, PreviewMode>);
+
+impl PreviewModes {
+    pub fn get(&self, total_pieces: &TotalPieces) -> PreviewMode {
+        match self.0.get(total_pieces) {
+            Some(mode) => *mode,
+            None if total_pieces.get_value() > 100 => PreviewMode::Both,
+            None => PreviewMode::default(),
+        }
+    }
+
+    pub fn set(&mut self, total_pieces: TotalPieces, mode: PreviewMode) {
+        self.0.insert(total_pieces, mode);
+    }
+}
+
+/// alpha of the picture under the board, changed with [ and ] while playing
+#[derive(Resource, Debug, PartialEq, Clone, Copy)]
+pub struct GhostOpacity(pub f32);
+
+impl Default for GhostOpacity {
+    fn default() -> Self {
+        GhostOpacity(0.2)
+    }
+}
@@ -0,0 +1,16 @@
+use bevy::prelude::Resource;
+use std::fmt::Display;
+
+/// difficulty mode where pieces spawn turned and must be rotated back
+#[derive(Resource, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct RotationMode(pub bool);
+
+impl Display for RotationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 {
+            write!(f, "rotation on")
+        } else {
+            write!(f, "rotation off")
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::config::rotation::RotationMode;
+use crate::config::total_pieces::TotalPieces;
+use bevy::prelude::Resource;
+use rand::{SeedableRng, StdRng};
+use std::fmt::Display;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// longest seed typed on the config screen, any 19 digit number fits in a u64
+pub const MAX_SEED_DIGITS: usize = 19;
+// piece counts of the daily puzzle, one is picked from the day's seed
+const DAILY_PIECES: [TotalPieces; 4] = [
+    TotalPieces::P35,
+    TotalPieces::P54,
+    TotalPieces::P96,
+    TotalPieces::P150,
+];
+
+/// seed of the puzzle being played, it decides the cut, where pieces start and how they are turned
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PuzzleSeed(pub u64);
+
+impl PuzzleSeed {
+    /// random numbers for one use of the seed, each `stream` gives its own sequence
+    pub fn get_rng(&self, stream: u64) -> StdRng {
+        let seed = mix(self.0 ^ stream);
+        StdRng::from_seed(&[(seed >> 32) as usize, seed as u32 as usize][..])
+    }
+}
+
+/// how the next puzzle gets its seed, set on the config screen
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub struct SeedEntry {
+    /// digits typed, a new random seed is drawn for every puzzle when empty
+    pub text: String,
+    /// the seed field takes the keyboard
+    pub typing: bool,
+    /// today's puzzle, the seed comes from the date
+    pub daily: bool,
+}
+
+impl SeedEntry {
+    pub fn get_seed(&self) -> Option<u64> {
+        if self.daily {
+            return Some(daily_seed(today()));
+        }
+        self.text.parse().ok()
+    }
+
+    /// whether pieces of the next puzzle start turned, the daily puzzle never turns them
+    /// whatever the setting
+    pub fn rotates(&self, rotation_mode: &RotationMode) -> bool {
+        rotation_mode.0 && !self.daily
+    }
+}
+
+impl Display for SeedEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.typing {
+            write!(f, "seed: {}_", self.text)
+        } else if self.daily {
+            write!(f, "daily seed")
+        } else if self.text.is_empty() {
+            write!(f, "random seed")
+        } else {
+            write!(f, "seed: {}", self.text)
+        }
+    }
+}
+
+/// days since the unix epoch, in utc so everyone shares the same day
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() / 86_400)
+}
+
+/// seed of the daily puzzle of `day`
+pub fn daily_seed(day: u64) -> u64 {
+    mix(day)
+}
+
+/// piece count of the daily puzzle with `seed`
+pub fn daily_pieces(seed: u64) -> TotalPieces {
+    DAILY_PIECES[(seed % DAILY_PIECES.len() as u64) as usize]
+}
+
+// splitmix64 finalizer, close numbers give unrelated seeds
+fn mix(value: u64) -> u64 {
+    let mut value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
@@ -1,57 +1,61 @@
-use crate::PAINT_BOARD_HEIGHT;
 use bevy::prelude::{Component, Resource};
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
-use strum::EnumIter;
+use strum::{EnumIter, IntoEnumIterator};
 
-#[derive(Resource, Debug, Component, PartialEq, Eq, Clone, Copy, EnumIter)]
+/// wanted number of pieces, the real grid depends on the picture shape
+#[derive(
+    Resource, Debug, Component, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Serialize, Deserialize,
+)]
 pub enum TotalPieces {
     P6,
+    P12,
     P24,
+    P35,
     P54,
     P96,
+    P150,
+    P300,
+    P500,
+    P1000,
 }
 
 impl TotalPieces {
-    pub fn get_height_count(&self) -> u8 {
-        2 * self.get_factor()
-    }
-
-    pub fn get_width_count(&self) -> u8 {
-        3 * self.get_factor()
-    }
-
-    fn get_factor(&self) -> u8 {
-        match self {
-            TotalPieces::P6 => 1,
-            TotalPieces::P24 => 2,
-            TotalPieces::P54 => 3,
-            TotalPieces::P96 => 4,
-        }
-    }
-
-    pub fn get_columns(&self) -> u8 {
-        self.get_factor() * 3
-    }
-
-    pub fn get_value(&self) -> u8 {
+    pub fn get_value(&self) -> u32 {
         match self {
             TotalPieces::P6 => 6,
+            TotalPieces::P12 => 12,
             TotalPieces::P24 => 24,
+            TotalPieces::P35 => 35,
             TotalPieces::P54 => 54,
             TotalPieces::P96 => 96,
+            TotalPieces::P150 => 150,
+            TotalPieces::P300 => 300,
+            TotalPieces::P500 => 500,
+            TotalPieces::P1000 => 1000,
         }
     }
 
-    pub fn get_side_length(&self) -> f32 {
-        PAINT_BOARD_HEIGHT / (self.get_height_count() as f32)
+    /// the count written as a number, e.g. on the command line
+    pub fn parse(count: &str) -> Result<TotalPieces, String> {
+        TotalPieces::iter()
+            .find(|pieces| pieces.to_string() == count)
+            .ok_or_else(|| {
+                let counts: Vec<String> = TotalPieces::iter()
+                    .map(|pieces| pieces.to_string())
+                    .collect();
+                format!("no {count} piece puzzle, pick one of {}", counts.join(", "))
+            })
     }
 
-    pub fn get_radius(&self) -> f32 {
-        (self.get_side_length() * self.get_side_length()) / 2.
-    }
+    /// columns and rows closest to the wanted count while keeping pieces nearly square
+    pub fn get_grid(&self, image_width: u32, image_height: u32) -> (u32, u32) {
+        let aspect = image_width as f32 / image_height as f32;
+        let value = self.get_value() as f32;
 
-    pub fn get_radius_half(&self) -> f32 {
-        self.get_radius() / 4.
+        let columns = (value * aspect).sqrt().round().max(1.);
+        let rows = (value / columns).round().max(1.);
+        (columns as u32, rows as u32)
     }
 }
 
@@ -0,0 +1,32 @@
+use bevy::prelude::Resource;
+use std::fmt::Display;
+
+const DEPTHS: [usize; 4] = [0, 10, 50, 200];
+
+/// how many drops can be undone, 0 turns undo off
+#[derive(Resource, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UndoDepth(pub usize);
+
+impl Default for UndoDepth {
+    fn default() -> Self {
+        UndoDepth(50)
+    }
+}
+
+impl UndoDepth {
+    /// the next depth offered on the config screen
+    pub fn next(&self) -> UndoDepth {
+        let index = DEPTHS.iter().position(|depth| *depth == self.0);
+        UndoDepth(DEPTHS[index.map_or(0, |index| (index + 1) % DEPTHS.len())])
+    }
+}
+
+impl Display for UndoDepth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 == 0 {
+            write!(f, "undo off")
+        } else {
+            write!(f, "undo {} drops", self.0)
+        }
+    }
+}
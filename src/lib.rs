@@ -0,0 +1,52 @@
+pub mod config;
+mod loading;
+pub mod model;
+pub mod play;
+mod stats;
+mod success;
+
+use bevy::prelude::*;
+use std::cmp::PartialEq;
+
+const BUTTON_DEFAULT_BACKGROUND: Color = Color::srgb(255., 255., 255.);
+const BUTTON_SELECTED_BACKGROUND: Color = Color::srgb(0., 255., 0.);
+
+const TEXT_COLOR: Color = Color::srgb(0., 0., 0.);
+
+// largest area the board may take, the picture is fitted inside
+const PAINT_BOARD_HEIGHT: f32 = 640.;
+const PAINT_BOARD_WIDTH: f32 = 960.;
+// todo get image from https://picsum.photos/id/1/1920/1280.jpg
+
+#[derive(States, Default, Clone, Eq, Debug, Hash, PartialEq)]
+pub enum GameState {
+    #[default]
+    Config,
+    Loading,
+    Play,
+    Success,
+    Stats,
+}
+
+fn despawn_screen<T: Component>(to_despawn: Query<Entity, With<T>>, mut commands: Commands) {
+    for entity in &to_despawn {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// states, screens and plugins of the game, the window and default plugins are up to the caller
+pub fn jigsaw_plugin(app: &mut App) {
+    app.add_systems(Startup, setup)
+        .init_state::<GameState>()
+        .add_plugins((
+            config::config_plugin,
+            loading::loading_plugin,
+            play::play_plugin,
+            success::success_plugin,
+            stats::stats_plugin,
+        ));
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+}
@@ -0,0 +1,229 @@
+use crate::config::level::Levels;
+use crate::config::seed::{PuzzleSeed, SeedEntry};
+use crate::config::total_pieces::TotalPieces;
+use crate::play::PuzzleImage;
+use crate::play::cut::PuzzleCut;
+use crate::play::layout::BoardLayout;
+use crate::play::save::RestoreGame;
+use crate::play::tray::get_color_groups;
+use crate::{GameState, TEXT_COLOR, despawn_screen};
+use bevy::asset::{LoadState, RenderAssetUsages};
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, poll_once};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// longest picture side kept, the sheet of cut pieces stays within texture limits
+const MAX_PICTURE_SIDE: u32 = 2048;
+
+pub fn loading_plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Loading), setup_loading)
+        .add_systems(
+            Update,
+            (load_picture, cut_picture)
+                .chain()
+                .run_if(in_state(GameState::Loading)),
+        )
+        .add_systems(
+            OnExit(GameState::Loading),
+            despawn_screen::<OnLoadingScreen>,
+        );
+}
+
+#[derive(Component)]
+struct OnLoadingScreen;
+
+#[derive(Component)]
+struct ProgressLabel;
+
+/// textures built off the main thread
+struct CutPicture {
+    source: Image,
+    sheet: Image,
+    outlines: Image,
+    size: UVec2,
+    color_groups: Vec<usize>,
+}
+
+/// picture of the level being loaded, then the pieces being cut out of it
+#[derive(Resource)]
+struct Loading {
+    path: String,
+    picture: Handle<Image>,
+    cutting: Option<Task<CutPicture>>,
+    pieces_done: Arc<AtomicUsize>,
+}
+
+fn setup_loading(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut level: ResMut<Levels>,
+    seed_entry: Res<SeedEntry>,
+    restore: Option<Res<RestoreGame>>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    // a saved puzzle keeps its seed, a new one takes the seed asked for or a random one
+    let seed = match restore {
+        Some(restore) => restore.0.seed,
+        None => seed_entry.get_seed().unwrap_or_else(rand::random),
+    };
+    commands.insert_resource(PuzzleSeed(seed));
+
+    let Some(level) = level.current_level() else {
+        level.report("no level to play".to_string());
+        state.set(GameState::Config);
+        return;
+    };
+
+    // level paths are relative to the working directory or absolute, not to the assets folder
+    let path = Path::new(level.get_path());
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    commands.insert_resource(Loading {
+        path: level.get_path().to_string(),
+        picture: asset_server.load_override(absolute),
+        cutting: None,
+        pieces_done: Arc::new(AtomicUsize::new(0)),
+    });
+
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(Color::WHITE),
+        children![
+            (
+                Text::new(format!("loading {}", level.get_title())),
+                TextColor(TEXT_COLOR),
+            ),
+            (
+                Text::new("reading picture"),
+                TextColor(TEXT_COLOR),
+                ProgressLabel,
+            )
+        ],
+        OnLoadingScreen,
+    ));
+}
+
+/// once the picture is decoded, build the layout and cut the pieces on the task pool
+#[allow(clippy::too_many_arguments)]
+fn load_picture(
+    mut commands: Commands,
+    loading: Option<ResMut<Loading>>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    total_pieces: Res<TotalPieces>,
+    seed: Res<PuzzleSeed>,
+    mut level: ResMut<Levels>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    let Some(mut loading) = loading else {
+        state.set(GameState::Config);
+        return;
+    };
+    if loading.cutting.is_some() {
+        return;
+    }
+
+    let picture = match asset_server.get_load_state(&loading.picture) {
+        Some(LoadState::Loaded) => images
+            .remove(&loading.picture)
+            .ok_or_else(|| "picture unloaded".to_string())
+            .and_then(|image| image.try_into_dynamic().map_err(|e| e.to_string())),
+        Some(LoadState::Failed(e)) => Err(e.to_string()),
+        _ => return,
+    };
+    let picture = match picture {
+        Ok(picture) => picture,
+        Err(e) => {
+            level.report(format!("can not read {}: {e}", loading.path));
+            commands.remove_resource::<Loading>();
+            commands.remove_resource::<RestoreGame>();
+            state.set(GameState::Config);
+            return;
+        }
+    };
+
+    let (width, height) = picture.dimensions();
+    let layout = BoardLayout::new(&total_pieces, width, height);
+    let cut = PuzzleCut::new(seed.0, layout.get_columns(), layout.get_rows());
+    commands.insert_resource(layout);
+    commands.insert_resource(cut.clone());
+
+    let pieces_done = loading.pieces_done.clone();
+    let outline_cell = layout.get_outline_cell_size();
+    loading.cutting = Some(
+        AsyncComputeTaskPool::get()
+            .spawn(async move { cut_sheet(picture, &cut, outline_cell, &pieces_done) }),
+    );
+}
+
+fn cut_sheet(
+    picture: DynamicImage,
+    cut: &PuzzleCut,
+    outline_cell: UVec2,
+    pieces_done: &AtomicUsize,
+) -> CutPicture {
+    let (width, height) = picture.dimensions();
+    let picture = if width.max(height) > MAX_PICTURE_SIDE {
+        picture.resize(MAX_PICTURE_SIDE, MAX_PICTURE_SIDE, FilterType::Triangle)
+    } else {
+        picture
+    };
+    let (width, height) = picture.dimensions();
+    let sheet = DynamicImage::ImageRgba8(cut.cut_sheet(&picture, pieces_done));
+    let color_groups = get_color_groups(&picture, cut.get_columns(), cut.get_rows());
+    let outlines = DynamicImage::ImageRgba8(cut.outline_sheet(outline_cell.x, outline_cell.y));
+
+    CutPicture {
+        // keep pixels on main world too, sprite picking reads alpha to ignore clicks between knobs
+        sheet: Image::from_dynamic(sheet, true, RenderAssetUsages::default()),
+        outlines: Image::from_dynamic(outlines, true, RenderAssetUsages::RENDER_WORLD),
+        source: Image::from_dynamic(picture, true, RenderAssetUsages::RENDER_WORLD),
+        size: UVec2::new(width, height),
+        color_groups,
+    }
+}
+
+/// show how far cutting went, start playing when it is done
+fn cut_picture(
+    mut commands: Commands,
+    loading: Option<ResMut<Loading>>,
+    layout: Option<Res<BoardLayout>>,
+    mut images: ResMut<Assets<Image>>,
+    mut labels: Query<&mut Text, With<ProgressLabel>>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    let (Some(mut loading), Some(layout)) = (loading, layout) else {
+        return;
+    };
+    let Some(task) = loading.cutting.as_mut() else {
+        return;
+    };
+
+    let Some(cut) = block_on(poll_once(task)) else {
+        let done = loading.pieces_done.load(Ordering::Relaxed);
+        for mut text in labels.iter_mut() {
+            *text = Text::new(format!("cutting pieces {done}/{}", layout.get_count()));
+        }
+        return;
+    };
+
+    commands.insert_resource(PuzzleImage {
+        source: images.add(cut.source),
+        sheet: images.add(cut.sheet),
+        outlines: images.add(cut.outlines),
+        size: cut.size,
+        color_groups: cut.color_groups,
+    });
+    commands.remove_resource::<Loading>();
+    state.set(GameState::Play);
+}
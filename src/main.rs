@@ -1,53 +1,45 @@
-mod config;
-mod play;
-mod success;
-
+use bevy::asset::UnapprovedPathMode;
 use bevy::prelude::*;
-use std::cmp::PartialEq;
-
-const BUTTON_DEFAULT_BACKGROUND: Color = Color::srgb(255., 255., 255.);
-const BUTTON_SELECTED_BACKGROUND: Color = Color::srgb(0., 255., 0.);
-
-const TEXT_COLOR: Color = Color::srgb(0., 0., 0.);
-
-// 3 * 2
-const PAINT_BOARD_HEIGHT: f32 = 640.;
-const PAINT_BOARD_WIDTH: f32 = 960.;
-// todo get image from https://picsum.photos/id/1/1920/1280.jpg
-
-#[derive(States, Default, Clone, Eq, Debug, Hash, PartialEq)]
-enum GameState {
-    #[default]
-    Config,
-    Play,
-    Success,
-}
+use bevy::window::{MonitorSelection, WindowMode};
+use my_jigsaw_game::config::launch::{Launch, USAGE};
+use my_jigsaw_game::jigsaw_plugin;
 
-fn despawn_screen<T: Component>(to_despawn: Query<Entity, With<T>>, mut commands: Commands) {
-    for entity in &to_despawn {
-        commands.entity(entity).despawn();
+fn main() {
+    let launch = match Launch::parse(std::env::args().skip(1)) {
+        Ok(launch) => launch,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    if launch.help {
+        println!("{USAGE}");
+        return;
     }
-}
+    let mode = if launch.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
 
-fn main() {
     App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Jigsaw".to_string(),
-                ..Default::default()
-            }),
-            ..Default::default()
-        }))
-        .add_systems(Startup, setup)
-        .init_state::<GameState>()
-        .add_plugins((
-            config::config_plugin,
-            play::play_plugin,
-            success::success_plugin,
-        ))
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Jigsaw".to_string(),
+                        mode,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                // level pictures live outside the assets folder
+                .set(AssetPlugin {
+                    unapproved_path_mode: UnapprovedPathMode::Deny,
+                    ..Default::default()
+                }),
+        )
+        .insert_resource(launch)
+        .add_plugins(jigsaw_plugin)
         .run();
 }
-
-fn setup(mut commands: Commands) {
-    commands.spawn(Camera2d);
-}
@@ -0,0 +1,249 @@
+use std::ops::{Add, Sub};
+
+/// position on the table, the board is centered on the origin and y grows upward
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Point { x, y }
+    }
+
+    pub fn length_squared(&self) -> f32 {
+        self.x * self.x + self.y * self.y
+    }
+
+    /// turned `quarters` quarter turns counter-clockwise around the origin
+    pub fn turn(&self, quarters: u8) -> Point {
+        (0..quarters % 4).fold(*self, |point, _| Point::new(-point.y, point.x))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+/// cells of the finished picture, piece `index` belongs in cell `index`, counted row by row
+/// from the top left
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grid {
+    columns: u32,
+    rows: u32,
+    width: f32,
+    height: f32,
+}
+
+impl Grid {
+    pub fn new(columns: u32, rows: u32, width: f32, height: f32) -> Self {
+        Grid {
+            columns,
+            rows,
+            width,
+            height,
+        }
+    }
+
+    pub fn get_columns(&self) -> u32 {
+        self.columns
+    }
+
+    pub fn get_rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn get_count(&self) -> usize {
+        (self.columns * self.rows) as usize
+    }
+
+    pub fn get_width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> f32 {
+        self.height
+    }
+
+    pub fn get_cell_width(&self) -> f32 {
+        self.width / self.columns as f32
+    }
+
+    pub fn get_cell_height(&self) -> f32 {
+        self.height / self.rows as f32
+    }
+
+    /// squared distance under which a piece snaps
+    pub fn get_radius_half(&self) -> f32 {
+        let side = self.get_cell_width().min(self.get_cell_height());
+        side * side / 8.
+    }
+
+    pub fn close_enough(&self, delta: Point) -> bool {
+        delta.length_squared() < self.get_radius_half()
+    }
+
+    /// center of cell `index`
+    pub fn get_slot_center(&self, index: usize) -> Point {
+        let column = index % self.columns as usize;
+        let row_from_bottom = self.rows as usize - 1 - index / self.columns as usize;
+        Point::new(
+            (column as f32 + 0.5) * self.get_cell_width() - self.width / 2.,
+            (row_from_bottom as f32 + 0.5) * self.get_cell_height() - self.height / 2.,
+        )
+    }
+
+    /// cell containing `position`, the snap radius is smaller than half a cell so no other
+    /// cell can be close enough
+    pub fn get_slot_at(&self, position: Point) -> Option<usize> {
+        let column = ((position.x + self.width / 2.) / self.get_cell_width()).floor();
+        let row_from_bottom = ((position.y + self.height / 2.) / self.get_cell_height()).floor();
+        if column < 0.
+            || row_from_bottom < 0.
+            || column >= self.columns as f32
+            || row_from_bottom >= self.rows as f32
+        {
+            return None;
+        }
+        let row = self.rows - 1 - row_from_bottom as u32;
+        Some((row * self.columns + column as u32) as usize)
+    }
+
+    /// cell a piece turned `quarters` away from upright would drop into at `position`
+    pub fn get_drop_slot(&self, position: Point, quarters: u8) -> Option<usize> {
+        if !quarters.is_multiple_of(4) {
+            return None;
+        }
+        self.get_slot_at(position)
+            .filter(|slot| self.close_enough(position - self.get_slot_center(*slot)))
+    }
+
+    /// whether cell `index` lies on the border of the picture, corners included
+    pub fn is_edge(&self, index: usize) -> bool {
+        let column = index as u32 % self.columns;
+        let row = index as u32 / self.columns;
+        column == 0 || row == 0 || column == self.columns - 1 || row == self.rows - 1
+    }
+
+    /// cells sharing a side with `index`
+    pub fn get_neighbours(&self, index: usize) -> Vec<usize> {
+        let columns = self.columns as usize;
+        let mut neighbours = vec![];
+        if !index.is_multiple_of(columns) {
+            neighbours.push(index - 1);
+        }
+        if index % columns != columns - 1 {
+            neighbours.push(index + 1);
+        }
+        if index >= columns {
+            neighbours.push(index - columns);
+        }
+        if index + columns < self.get_count() {
+            neighbours.push(index + columns);
+        }
+        neighbours
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 4 columns of 25 by 3 rows of 20
+    fn grid() -> Grid {
+        Grid::new(4, 3, 100., 60.)
+    }
+
+    #[test]
+    fn point_turns_counter_clockwise() {
+        let point = Point::new(2., 1.);
+        assert_eq!(point.turn(0), point);
+        assert_eq!(point.turn(1), Point::new(-1., 2.));
+        assert_eq!(point.turn(2), Point::new(-2., -1.));
+        assert_eq!(point.turn(3), Point::new(1., -2.));
+        assert_eq!(point.turn(4), point);
+    }
+
+    #[test]
+    fn cells() {
+        let grid = grid();
+        assert_eq!(grid.get_count(), 12);
+        assert_eq!(grid.get_cell_width(), 25.);
+        assert_eq!(grid.get_cell_height(), 20.);
+        assert_eq!(grid.get_radius_half(), 50.);
+    }
+
+    #[test]
+    fn slot_centers_start_top_left() {
+        let grid = grid();
+        assert_eq!(grid.get_slot_center(0), Point::new(-37.5, 20.));
+        assert_eq!(grid.get_slot_center(3), Point::new(37.5, 20.));
+        assert_eq!(grid.get_slot_center(11), Point::new(37.5, -20.));
+    }
+
+    #[test]
+    fn slot_at_finds_every_center() {
+        let grid = grid();
+        for index in 0..grid.get_count() {
+            assert_eq!(grid.get_slot_at(grid.get_slot_center(index)), Some(index));
+        }
+    }
+
+    #[test]
+    fn slot_at_is_none_off_the_board() {
+        let grid = grid();
+        assert_eq!(grid.get_slot_at(Point::new(-51., 0.)), None);
+        assert_eq!(grid.get_slot_at(Point::new(50., 0.)), None);
+        assert_eq!(grid.get_slot_at(Point::new(0., 31.)), None);
+        assert_eq!(grid.get_slot_at(Point::new(0., -31.)), None);
+    }
+
+    #[test]
+    fn drop_slot_needs_upright_and_close() {
+        let grid = grid();
+        let center = grid.get_slot_center(5);
+        assert_eq!(grid.get_drop_slot(center + Point::new(3., 3.), 0), Some(5));
+        assert_eq!(grid.get_drop_slot(center + Point::new(3., 3.), 4), Some(5));
+        assert_eq!(grid.get_drop_slot(center, 1), None);
+        // inside the cell but too far from its center
+        assert_eq!(grid.get_drop_slot(center + Point::new(10., 0.), 0), None);
+    }
+
+    #[test]
+    fn edges() {
+        let grid = grid();
+        let edges: Vec<usize> = (0..grid.get_count()).filter(|i| grid.is_edge(*i)).collect();
+        assert_eq!(edges, vec![0, 1, 2, 3, 4, 7, 8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn neighbours_stay_in_their_row() {
+        let grid = grid();
+        assert_eq!(grid.get_neighbours(0), vec![1, 4]);
+        assert_eq!(grid.get_neighbours(3), vec![2, 7]);
+        assert_eq!(grid.get_neighbours(4), vec![5, 0, 8]);
+        assert_eq!(grid.get_neighbours(5), vec![4, 6, 1, 9]);
+        assert_eq!(grid.get_neighbours(11), vec![10, 7]);
+    }
+
+    #[test]
+    fn neighbours_of_a_single_row() {
+        let grid = Grid::new(3, 1, 30., 10.);
+        assert_eq!(grid.get_neighbours(0), vec![1]);
+        assert_eq!(grid.get_neighbours(1), vec![0, 2]);
+        assert_eq!(grid.get_neighbours(2), vec![1]);
+    }
+}
@@ -0,0 +1,432 @@
+//! rules of the puzzle without bevy: where pieces belong, which ones fit together and when the
+//! picture is done; the play systems copy the pieces in and apply what comes out
+
+mod grid;
+
+pub use grid::{Grid, Point};
+
+/// one piece as the rules see it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PieceState {
+    /// cell the piece belongs in
+    pub index: usize,
+    pub position: Point,
+    /// quarter turns counter-clockwise away from upright
+    pub quarters: u8,
+    /// cell of the board the piece lies in
+    pub slot: Option<usize>,
+    /// pieces snapped together off the board share a group
+    pub group: Option<usize>,
+    pub in_hand: bool,
+}
+
+impl PieceState {
+    /// upright piece lying loose on the table
+    pub fn new(index: usize, position: Point) -> Self {
+        PieceState {
+            index,
+            position,
+            quarters: 0,
+            slot: None,
+            group: None,
+            in_hand: false,
+        }
+    }
+
+    pub fn is_upright(&self) -> bool {
+        self.quarters.is_multiple_of(4)
+    }
+}
+
+/// move bringing released pieces against their neighbours on the table
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snap {
+    pub delta: Point,
+    /// pieces the released ones fit with once moved, by their place in the model
+    pub joined: Vec<usize>,
+}
+
+/// where released pieces land on the board
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    /// move of every released piece, the group keeps its shape
+    pub delta: Point,
+    /// released pieces taking a board cell once moved, by their place in the model, with the cell
+    pub placed: Vec<(usize, usize)>,
+}
+
+/// every piece of a puzzle, a piece is named by its place in the list
+#[derive(Debug, Clone, PartialEq)]
+pub struct PuzzleModel {
+    grid: Grid,
+    pieces: Vec<PieceState>,
+}
+
+impl PuzzleModel {
+    pub fn new(grid: Grid, pieces: Vec<PieceState>) -> Self {
+        PuzzleModel { grid, pieces }
+    }
+
+    pub fn get_grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn get_pieces(&self) -> &[PieceState] {
+        &self.pieces
+    }
+
+    /// pieces lying in the board cells around piece `id`, picking around takes them along
+    pub fn get_around(&self, id: usize) -> Vec<usize> {
+        let Some(slot) = self.pieces[id].slot else {
+            return vec![];
+        };
+        let neighbours = self.grid.get_neighbours(slot);
+        self.pieces
+            .iter()
+            .enumerate()
+            .filter(|(_, piece)| piece.slot.is_some_and(|slot| neighbours.contains(&slot)))
+            .map(|(other, _)| other)
+            .collect()
+    }
+
+    /// where the `released` pieces fit with a real neighbour lying loose on the table and
+    /// turned the same way, the first neighbour close enough decides the move
+    pub fn find_snap(&self, released: &[usize]) -> Option<Snap> {
+        let quarters = self.pieces[*released.first()?].quarters;
+
+        let mut table = vec![None; self.grid.get_count()];
+        for (id, piece) in self.pieces.iter().enumerate() {
+            if !piece.in_hand
+                && piece.slot.is_none()
+                && piece.quarters == quarters
+                && !released.contains(&id)
+            {
+                table[piece.index] = Some(id);
+            }
+        }
+
+        let mut gaps = vec![];
+        for piece in released.iter().map(|id| &self.pieces[*id]) {
+            let correct = self.grid.get_slot_center(piece.index);
+            for neighbour in self.grid.get_neighbours(piece.index) {
+                if let Some(other) = table[neighbour] {
+                    let offset = (self.grid.get_slot_center(neighbour) - correct).turn(quarters);
+                    gaps.push((
+                        self.pieces[other].position - (piece.position + offset),
+                        other,
+                    ));
+                }
+            }
+        }
+
+        let delta = gaps
+            .iter()
+            .map(|(gap, _)| *gap)
+            .find(|gap| self.grid.close_enough(*gap))?;
+        let mut joined = vec![];
+        for (gap, other) in gaps {
+            if self.grid.close_enough(gap - delta) && !joined.contains(&other) {
+                joined.push(other);
+            }
+        }
+        Some(Snap { delta, joined })
+    }
+
+    /// where the `released` pieces drop on the board: the first one upright over a free cell
+    /// brings them all onto its center, then every one over a free cell takes it; a cell is
+    /// free when no other piece of the model lies in it
+    pub fn place_group(&self, released: &[usize]) -> Option<Placement> {
+        let mut taken = vec![false; self.grid.get_count()];
+        for (id, piece) in self.pieces.iter().enumerate() {
+            if let Some(slot) = piece.slot.filter(|_| !released.contains(&id)) {
+                taken[slot] = true;
+            }
+        }
+        let free_cell = |piece: &PieceState, delta: Point| {
+            self.grid
+                .get_drop_slot(piece.position + delta, piece.quarters)
+                .filter(|cell| !taken[*cell])
+        };
+
+        let delta = released.iter().find_map(|id| {
+            let piece = &self.pieces[*id];
+            let cell = free_cell(piece, Point::default())?;
+            Some(self.grid.get_slot_center(cell) - piece.position)
+        })?;
+        let mut placed: Vec<(usize, usize)> = vec![];
+        for id in released {
+            if let Some(cell) = free_cell(&self.pieces[*id], delta)
+                && !placed.iter().any(|(_, other)| *other == cell)
+            {
+                placed.push((*id, cell));
+            }
+        }
+        Some(Placement { delta, placed })
+    }
+
+    /// done when every piece lies upright in its own cell, or when all pieces snapped into
+    /// one upright group, wherever it lies
+    pub fn is_solved(&self) -> bool {
+        if self.pieces.len() != self.grid.get_count() {
+            return false;
+        }
+        let all_placed = self
+            .pieces
+            .iter()
+            .all(|piece| piece.is_upright() && piece.slot == Some(piece.index));
+        let one_group = self.pieces.first().is_some_and(|first| {
+            first.group.is_some()
+                && first.is_upright()
+                && self.pieces.iter().all(|piece| piece.group == first.group)
+        });
+        all_placed || one_group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 columns by 2 rows of 20 by 20 cells, snap distance under 50 squared
+    fn grid() -> Grid {
+        Grid::new(3, 2, 60., 40.)
+    }
+
+    /// every piece upright in its own cell
+    fn solved() -> PuzzleModel {
+        let grid = grid();
+        let pieces = (0..grid.get_count())
+            .map(|index| PieceState {
+                slot: Some(index),
+                ..PieceState::new(index, grid.get_slot_center(index))
+            })
+            .collect();
+        PuzzleModel::new(grid, pieces)
+    }
+
+    /// pieces far apart on the table, none placed
+    fn scattered() -> PuzzleModel {
+        let pieces = (0..6)
+            .map(|index| PieceState::new(index, Point::new(200. + index as f32 * 100., 0.)))
+            .collect();
+        PuzzleModel::new(grid(), pieces)
+    }
+
+    #[test]
+    fn around_takes_board_neighbours_only() {
+        let mut model = solved();
+        // piece 2 sits on the table, its cell is empty
+        model.pieces[2].slot = None;
+        assert_eq!(model.get_around(1), vec![0, 4]);
+    }
+
+    #[test]
+    fn around_stays_in_the_row() {
+        // the start of a row is not next to the end of the row above
+        assert_eq!(solved().get_around(3), vec![0, 4]);
+        assert_eq!(solved().get_around(2), vec![1, 5]);
+    }
+
+    #[test]
+    fn around_is_empty_off_the_board() {
+        let mut model = solved();
+        model.pieces[0].slot = None;
+        assert!(model.get_around(0).is_empty());
+    }
+
+    #[test]
+    fn snap_to_the_right_neighbour() {
+        let mut model = scattered();
+        // piece 1 belongs 20 right of piece 0
+        model.pieces[0].position = Point::new(100., 100.);
+        model.pieces[1].position = Point::new(123., 101.);
+        let snap = model.find_snap(&[0]).unwrap();
+        assert_eq!(snap.delta, Point::new(3., 1.));
+        assert_eq!(snap.joined, vec![1]);
+    }
+
+    #[test]
+    fn snap_follows_the_turn() {
+        let mut model = scattered();
+        model.pieces[0].quarters = 1;
+        model.pieces[1].quarters = 1;
+        // a quarter turn counter-clockwise puts the right neighbour above
+        model.pieces[0].position = Point::new(100., 100.);
+        model.pieces[1].position = Point::new(100., 120.);
+        let snap = model.find_snap(&[0]).unwrap();
+        assert_eq!(snap.delta, Point::new(0., 0.));
+        assert_eq!(snap.joined, vec![1]);
+    }
+
+    #[test]
+    fn no_snap_with_another_turn() {
+        let mut model = scattered();
+        model.pieces[1].quarters = 1;
+        model.pieces[0].position = Point::new(100., 100.);
+        model.pieces[1].position = Point::new(120., 100.);
+        assert_eq!(model.find_snap(&[0]), None);
+    }
+
+    #[test]
+    fn no_snap_when_far() {
+        let mut model = scattered();
+        model.pieces[0].position = Point::new(100., 100.);
+        model.pieces[1].position = Point::new(130., 100.);
+        assert_eq!(model.find_snap(&[0]), None);
+    }
+
+    #[test]
+    fn no_snap_with_pieces_in_hand_or_on_the_board() {
+        let mut model = scattered();
+        model.pieces[0].position = Point::new(100., 100.);
+        model.pieces[1].position = Point::new(120., 100.);
+        model.pieces[1].in_hand = true;
+        model.pieces[3].position = Point::new(100., 80.);
+        model.pieces[3].slot = Some(3);
+        assert_eq!(model.find_snap(&[0]), None);
+    }
+
+    #[test]
+    fn no_snap_between_released_pieces() {
+        let mut model = scattered();
+        model.pieces[0].position = Point::new(100., 100.);
+        model.pieces[1].position = Point::new(120., 100.);
+        assert_eq!(model.find_snap(&[0, 1]), None);
+        assert_eq!(model.find_snap(&[]), None);
+    }
+
+    #[test]
+    fn snap_joins_every_fitting_neighbour() {
+        let mut model = scattered();
+        // piece 4 dropped in the middle of the bottom row, 0 and 2 lie elsewhere
+        model.pieces[4].position = Point::new(101., 99.);
+        model.pieces[3].position = Point::new(80., 100.);
+        model.pieces[5].position = Point::new(120., 100.);
+        model.pieces[1].position = Point::new(100., 120.);
+        let snap = model.find_snap(&[4]).unwrap();
+        assert_eq!(snap.delta, Point::new(-1., 1.));
+        let mut joined = snap.joined;
+        joined.sort();
+        assert_eq!(joined, vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn snap_moves_a_released_group() {
+        let mut model = scattered();
+        model.pieces[0].position = Point::new(100., 100.);
+        model.pieces[1].position = Point::new(120., 100.);
+        model.pieces[2].position = Point::new(142., 100.);
+        let snap = model.find_snap(&[0, 1]).unwrap();
+        assert_eq!(snap.delta, Point::new(2., 0.));
+        assert_eq!(snap.joined, vec![2]);
+    }
+
+    #[test]
+    fn place_a_piece_on_the_cell_under_it() {
+        let mut model = scattered();
+        // cell 4 is centered on (0, -10)
+        model.pieces[2].position = Point::new(2., -11.);
+        let placement = model.place_group(&[2]).unwrap();
+        assert_eq!(placement.delta, Point::new(-2., 1.));
+        assert_eq!(placement.placed, vec![(2, 4)]);
+    }
+
+    #[test]
+    fn place_a_group_keeps_its_shape() {
+        let mut model = scattered();
+        // piece 0 over cell 0 brings piece 1 onto cell 1, piece 5 stays off the board
+        model.pieces[0].position = Point::new(-19., 12.);
+        model.pieces[1].position = Point::new(1., 12.);
+        model.pieces[5].position = Point::new(81., 12.);
+        let placement = model.place_group(&[0, 1, 5]).unwrap();
+        assert_eq!(placement.delta, Point::new(-1., -2.));
+        assert_eq!(placement.placed, vec![(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn place_skips_taken_cells() {
+        let mut model = scattered();
+        model.pieces[3].slot = Some(1);
+        model.pieces[0].position = Point::new(-20., 10.);
+        model.pieces[1].position = Point::new(0., 10.);
+        let placement = model.place_group(&[0, 1]).unwrap();
+        assert_eq!(placement.placed, vec![(0, 0)]);
+
+        // with its only cell taken the piece is not placed at all
+        assert_eq!(model.place_group(&[1]), None);
+    }
+
+    #[test]
+    fn place_frees_the_cells_of_released_pieces() {
+        let mut model = solved();
+        model.pieces[0].position = Point::new(0., 11.);
+        model.pieces[1].position = Point::new(-20., 11.);
+        let placement = model.place_group(&[0, 1]).unwrap();
+        assert_eq!(placement.placed, vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn no_place_off_the_board_or_turned() {
+        let mut model = scattered();
+        assert_eq!(model.place_group(&[0]), None);
+        assert_eq!(model.place_group(&[]), None);
+
+        model.pieces[0].position = Point::new(-20., 10.);
+        model.pieces[0].quarters = 2;
+        assert_eq!(model.place_group(&[0]), None);
+    }
+
+    #[test]
+    fn solved_when_all_placed() {
+        assert!(solved().is_solved());
+    }
+
+    #[test]
+    fn not_solved_with_a_piece_in_the_wrong_cell() {
+        let mut model = solved();
+        model.pieces[0].slot = Some(1);
+        model.pieces[1].slot = Some(0);
+        assert!(!model.is_solved());
+    }
+
+    #[test]
+    fn not_solved_with_a_turned_piece() {
+        let mut model = solved();
+        model.pieces[2].quarters = 3;
+        assert!(!model.is_solved());
+    }
+
+    #[test]
+    fn not_solved_with_a_piece_missing() {
+        let mut model = solved();
+        model.pieces.pop();
+        assert!(!model.is_solved());
+    }
+
+    #[test]
+    fn solved_as_one_upright_group() {
+        let mut model = scattered();
+        for piece in model.pieces.iter_mut() {
+            piece.group = Some(7);
+        }
+        assert!(model.is_solved());
+
+        model.pieces[0].group = Some(8);
+        assert!(!model.is_solved());
+    }
+
+    #[test]
+    fn not_solved_as_one_turned_group() {
+        let mut model = scattered();
+        for piece in model.pieces.iter_mut() {
+            piece.group = Some(7);
+            piece.quarters = 1;
+        }
+        assert!(!model.is_solved());
+    }
+
+    #[test]
+    fn loose_pieces_are_not_solved() {
+        assert!(!scattered().is_solved());
+    }
+}
@@ -1,6 +1,7 @@
-use crate::config::total_pieces::TotalPieces;
-use crate::play::{CorrectIndex, OnPlayScreen, PreUnder, get_correct_position};
-use bevy::asset::Assets;
+use crate::play::cut::PuzzleCut;
+use crate::play::hint::{HINT_COLOR, Hinted};
+use crate::play::layout::BoardLayout;
+use crate::play::{CorrectIndex, OnPlayScreen, PreUnder, PuzzleImage, get_correct_position};
 use bevy::color::Color;
 use bevy::prelude::*;
 
@@ -12,38 +13,75 @@ pub struct Board {
     pub(crate) index: CorrectIndex,
 }
 
+/// board slot of every index, found from a position instead of scanning all slots
+#[derive(Resource)]
+pub struct BoardSlots(pub(super) Vec<Entity>);
+
+impl BoardSlots {
+    pub fn get(&self, index: CorrectIndex) -> Option<Entity> {
+        self.0.get(index).copied()
+    }
+}
+
+/// one sprite per slot, all cut from the sheet of outlines made while loading
 pub fn setup_board(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    total_pieces: Res<TotalPieces>,
+    puzzle_image: Res<PuzzleImage>,
+    layout: Res<BoardLayout>,
+    cut: Res<PuzzleCut>,
 ) {
-    for index in 0..total_pieces.get_value() {
-        let index = index as usize;
-        let correct_position = get_correct_position(index, &total_pieces);
-        commands.spawn((
-            Mesh2d(meshes.add(Rectangle::new(
-                total_pieces.get_side_length(),
-                total_pieces.get_side_length(),
-            ))),
-            MeshMaterial2d(materials.add(PAINT_BOARD_COLOR)),
-            correct_position,
+    let cell = layout.get_outline_cell_size();
+    let (sheet_width, sheet_height) = (cell.x * layout.get_columns(), cell.y * layout.get_rows());
+
+    let mut slots = vec![];
+    for index in 0..layout.get_count() {
+        let rect = cut.get_sheet_rect(index, sheet_width, sheet_height);
+        let mut sprite = Sprite::from_image(puzzle_image.outlines.clone());
+        sprite.rect = Some(rect);
+        sprite.custom_size = Some(Vec2::new(
+            rect.width() * layout.get_cell_width() / cell.x as f32,
+            rect.height() * layout.get_cell_height() / cell.y as f32,
+        ));
+        sprite.color = PAINT_BOARD_COLOR;
+        let slot = commands.spawn((
+            sprite,
+            get_correct_position(index, &layout),
+            // drops find their slot from the position, the slots never take clicks
+            Pickable::IGNORE,
             Board { index },
             OnPlayScreen,
         ));
+        slots.push(slot.id());
     }
+    commands.insert_resource(BoardSlots(slots));
 }
 
+type SlotLook = (&'static mut Sprite, Has<PreUnder>, Option<&'static Hinted>);
+type SlotLookChanged = Or<(Added<PreUnder>, Changed<Hinted>)>;
+
+/// recolor the slots whose preselection or hint changed, the others keep their color
 pub fn draw_board_color(
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    pre_under: Query<&PreUnder>,
-    mut correct_positions: Query<(&MeshMaterial2d<ColorMaterial>, Entity), With<Board>>,
+    mut slots: Query<SlotLook, With<Board>>,
+    changed: Query<Entity, (With<Board>, SlotLookChanged)>,
+    mut pre_under_removed: RemovedComponents<PreUnder>,
+    mut hint_removed: RemovedComponents<Hinted>,
 ) {
-    for (mesh_material, entity) in correct_positions.iter_mut() {
-        materials.get_mut(mesh_material.id()).unwrap().color = if pre_under.get(entity).is_ok() {
+    let touched: Vec<Entity> = changed
+        .iter()
+        .chain(pre_under_removed.read())
+        .chain(hint_removed.read())
+        .collect();
+    for entity in touched {
+        // removals also come from pieces and despawned slots
+        let Ok((mut sprite, pre_under, hinted)) = slots.get_mut(entity) else {
+            continue;
+        };
+        sprite.color = if pre_under {
             PAINT_PRE_SELECT_COLOR
+        } else if hinted.is_some_and(|hinted| hinted.is_lit()) {
+            HINT_COLOR
         } else {
             PAINT_BOARD_COLOR
-        }
+        };
     }
 }
@@ -0,0 +1,137 @@
+use crate::config::rotation::RotationMode;
+use crate::config::seed::SeedEntry;
+use crate::play::MoveState;
+use crate::play::layout::BoardLayout;
+use crate::play::piece::Piece;
+use crate::play::pointer::Cursor;
+use crate::play::tray::Tray;
+use bevy::input::mouse::{AccumulatedMouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+// projection scale bounds, above 1 the table is seen from further away
+const MIN_ZOOM: f32 = 0.2;
+const MAX_ZOOM: f32 = 8.;
+// scale factor of one wheel line
+const ZOOM_STEP: f32 = 1.1;
+const PIXELS_PER_LINE: f32 = 40.;
+// room left around the pieces when fitting them all, relative to the window
+const FIT_MARGIN: f32 = 1.05;
+
+/// the wheel turns the pieces in hand in rotation mode, it zooms otherwise
+pub(super) fn wheel_zooms(
+    state: Res<State<MoveState>>,
+    rotation_mode: Res<RotationMode>,
+    seed_entry: Res<SeedEntry>,
+) -> bool {
+    *state.get() == MoveState::Init || !seed_entry.rotates(&rotation_mode)
+}
+
+/// zoom with the wheel, the point under the cursor stays in place
+pub(super) fn zoom_camera(
+    mut wheel: EventReader<MouseWheel>,
+    cursor: Res<Cursor>,
+    mut q_camera: Query<(&mut Transform, &mut Projection), With<Camera>>,
+) {
+    let lines: f32 = wheel
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE,
+        })
+        .sum();
+    if lines == 0. {
+        return;
+    }
+    let Ok((mut transform, mut projection)) = q_camera.single_mut() else {
+        return;
+    };
+    let Projection::Orthographic(orthographic) = projection.as_mut() else {
+        return;
+    };
+
+    let old_scale = orthographic.scale;
+    let new_scale = (old_scale * ZOOM_STEP.powf(-lines)).clamp(MIN_ZOOM, MAX_ZOOM);
+    orthographic.scale = new_scale;
+
+    if let Some(anchor) = cursor.0 {
+        let offset = transform.translation.truncate() - anchor;
+        let center = anchor + offset * new_scale / old_scale;
+        transform.translation.x = center.x;
+        transform.translation.y = center.y;
+    }
+}
+
+/// drag the table with the middle button, or with any button while space is held
+pub(super) fn pan_camera(
+    motion: Res<AccumulatedMouseMotion>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut q_camera: Query<(&mut Transform, &Projection), With<Camera>>,
+) {
+    let dragging = buttons.pressed(MouseButton::Middle)
+        || (keys.pressed(KeyCode::Space) && buttons.get_pressed().next().is_some());
+    if motion.delta == Vec2::ZERO || !dragging {
+        return;
+    }
+    let Ok((mut transform, projection)) = q_camera.single_mut() else {
+        return;
+    };
+    let scale = match projection {
+        Projection::Orthographic(orthographic) => orthographic.scale,
+        _ => 1.,
+    };
+    // screen y grows downward, world y upward
+    transform.translation.x -= motion.delta.x * scale;
+    transform.translation.y += motion.delta.y * scale;
+}
+
+/// what fitting the view keeps on screen besides the board
+type ShownByFit = (Or<(With<Piece>, With<Tray>)>, Without<Camera>);
+
+pub(super) fn fit_all_key(
+    keys: Res<ButtonInput<KeyCode>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&mut Transform, &mut Projection), With<Camera>>,
+    layout: Res<BoardLayout>,
+    sprites: Query<(&Transform, &Sprite), ShownByFit>,
+) {
+    if keys.just_pressed(KeyCode::KeyF) {
+        fit_all(q_window, q_camera, layout, sprites);
+    }
+}
+
+/// center the camera on the board, the trays and every piece, zooming out until they all show
+pub(super) fn fit_all(
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_camera: Query<(&mut Transform, &mut Projection), With<Camera>>,
+    layout: Res<BoardLayout>,
+    sprites: Query<(&Transform, &Sprite), ShownByFit>,
+) {
+    let (Ok(window), Ok((mut transform, mut projection))) =
+        (q_window.single(), q_camera.single_mut())
+    else {
+        return;
+    };
+    let Projection::Orthographic(orthographic) = projection.as_mut() else {
+        return;
+    };
+
+    // the board slots are meshes, the board is taken from the layout, centered on the origin
+    let mut bounds = Rect::from_center_size(
+        Vec2::ZERO,
+        Vec2::new(layout.get_board_width(), layout.get_board_height()),
+    );
+    for (sprite_transform, sprite) in sprites.iter() {
+        let half = sprite.custom_size.unwrap_or(Vec2::ZERO) / 2.;
+        let center = sprite_transform.translation.truncate();
+        bounds = bounds.union(Rect::from_center_half_size(center, half));
+    }
+
+    let size = bounds.size() * FIT_MARGIN;
+    orthographic.scale = (size.x / window.width())
+        .max(size.y / window.height())
+        .clamp(MIN_ZOOM, MAX_ZOOM);
+    transform.translation.x = bounds.center().x;
+    transform.translation.y = bounds.center().y;
+}
@@ -0,0 +1,360 @@
+use bevy::prelude::*;
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+use rand::{Rng, SeedableRng, StdRng};
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// knob radius, relative to the shorter side of a cell
+const KNOB_RADIUS: f32 = 0.18;
+// distance of the knob center from the edge, relative to the knob radius
+const KNOB_OFFSET: f32 = 0.75;
+
+const OUTLINE_WIDTH: f32 = 2.;
+const OUTLINE_COLOR: Rgba<u8> = Rgba([150, 150, 150, 255]);
+const FILL_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Edge {
+    Flat,
+    Tab,
+    Blank,
+}
+
+impl Edge {
+    fn opposite(&self) -> Edge {
+        match self {
+            Edge::Flat => Edge::Flat,
+            Edge::Tab => Edge::Blank,
+            Edge::Blank => Edge::Tab,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PieceShape {
+    pub top: Edge,
+    pub right: Edge,
+    pub bottom: Edge,
+    pub left: Edge,
+}
+
+impl PieceShape {
+    /// whether point (x, y) belongs to the piece, coordinates are relative to the
+    /// top left corner of the cell, y grows downward like in the source image
+    pub fn contains(&self, width: f32, height: f32, x: f32, y: f32) -> bool {
+        let radius = KNOB_RADIUS * width.min(height);
+        let offset = KNOB_OFFSET * radius;
+
+        let sides = [
+            (self.top, width / 2., 0., 0., -1.),
+            (self.right, width, height / 2., 1., 0.),
+            (self.bottom, width / 2., height, 0., 1.),
+            (self.left, 0., height / 2., -1., 0.),
+        ];
+
+        let mut in_tab = false;
+        let mut in_blank = false;
+        for (edge, mid_x, mid_y, normal_x, normal_y) in sides {
+            let direction = match edge {
+                Edge::Flat => continue,
+                Edge::Tab => 1.,
+                Edge::Blank => -1.,
+            };
+            let delta_x = x - (mid_x + direction * normal_x * offset);
+            let delta_y = y - (mid_y + direction * normal_y * offset);
+            if delta_x * delta_x + delta_y * delta_y < radius * radius {
+                match edge {
+                    Edge::Tab => in_tab = true,
+                    _ => in_blank = true,
+                }
+            }
+        }
+
+        let in_cell = x >= 0. && x < width && y >= 0. && y < height;
+        in_tab || (in_cell && !in_blank)
+    }
+}
+
+/// tab and blank layout of every piece, generated from a seed
+#[derive(Resource, Debug, Clone)]
+pub struct PuzzleCut {
+    seed: u64,
+    columns: u32,
+    rows: u32,
+    shapes: Vec<PieceShape>,
+}
+
+impl PuzzleCut {
+    pub fn new(seed: u64, columns: u32, rows: u32) -> Self {
+        let mut rng = StdRng::from_seed(&[(seed >> 32) as usize, seed as u32 as usize][..]);
+
+        let flat = PieceShape {
+            top: Edge::Flat,
+            right: Edge::Flat,
+            bottom: Edge::Flat,
+            left: Edge::Flat,
+        };
+        let mut shapes = vec![flat; (columns * rows) as usize];
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let index = (row * columns + column) as usize;
+                if column + 1 < columns {
+                    let edge = if rng.r#gen::<bool>() {
+                        Edge::Tab
+                    } else {
+                        Edge::Blank
+                    };
+                    shapes[index].right = edge;
+                    shapes[index + 1].left = edge.opposite();
+                }
+                if row + 1 < rows {
+                    let edge = if rng.r#gen::<bool>() {
+                        Edge::Tab
+                    } else {
+                        Edge::Blank
+                    };
+                    shapes[index].bottom = edge;
+                    shapes[index + columns as usize].top = edge.opposite();
+                }
+            }
+        }
+
+        PuzzleCut {
+            seed,
+            columns,
+            rows,
+            shapes,
+        }
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_columns(&self) -> u32 {
+        self.columns
+    }
+
+    pub fn get_rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn get_shape(&self, index: usize) -> PieceShape {
+        self.shapes[index]
+    }
+
+    /// room needed around a cell so the knobs fit in the piece image
+    pub fn get_padding(width: f32, height: f32) -> f32 {
+        KNOB_RADIUS * (1. + KNOB_OFFSET) * width.min(height)
+    }
+
+    /// cut piece `index` out of the whole picture, pixels outside the piece are transparent
+    pub fn cut_piece(&self, img: &DynamicImage, index: usize) -> RgbaImage {
+        let (width, height) = img.dimensions();
+        let (slot_width, slot_height) = self.get_slot_size(width, height);
+        let mut piece = RgbaImage::new(slot_width, slot_height);
+        self.write_piece(img, index, &mut piece, 0, 0);
+        piece
+    }
+
+    /// every piece cut out of the picture, side by side in one sheet following the grid,
+    /// see `get_sheet_rect` for where a piece lies; `pieces_done` counts the pieces cut so far
+    pub fn cut_sheet(&self, img: &DynamicImage, pieces_done: &AtomicUsize) -> RgbaImage {
+        let (width, height) = img.dimensions();
+        let (slot_width, slot_height) = self.get_slot_size(width, height);
+        let mut sheet = RgbaImage::new(slot_width * self.columns, slot_height * self.rows);
+        for index in 0..self.shapes.len() {
+            let column = index as u32 % self.columns;
+            let row = index as u32 / self.columns;
+            self.write_piece(
+                img,
+                index,
+                &mut sheet,
+                column * slot_width,
+                row * slot_height,
+            );
+            pieces_done.fetch_add(1, Ordering::Relaxed);
+        }
+        sheet
+    }
+
+    /// pixel area of piece `index` in the sheet cut from a `width` x `height` picture
+    pub fn get_sheet_rect(&self, index: usize, width: u32, height: u32) -> Rect {
+        let (slot_width, slot_height) = self.get_slot_size(width, height);
+        let column = index as u32 % self.columns;
+        let row = index as u32 / self.columns;
+        let min = Vec2::new((column * slot_width) as f32, (row * slot_height) as f32);
+        Rect::from_corners(min, min + Vec2::new(slot_width as f32, slot_height as f32))
+    }
+
+    /// size of one cell of a `width` x `height` picture, in pixel
+    pub fn get_cell_size(&self, width: u32, height: u32) -> (u32, u32) {
+        (width / self.columns, height / self.rows)
+    }
+
+    /// size of one piece image, the cell and room for the knobs
+    fn get_slot_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let (cell_width, cell_height) = self.get_cell_size(width, height);
+        let padding = Self::get_padding(cell_width as f32, cell_height as f32).ceil() as u32;
+        (cell_width + 2 * padding, cell_height + 2 * padding)
+    }
+
+    /// copy the pixels of piece `index` to `target`, its padded cell starting at (left, top)
+    fn write_piece(
+        &self,
+        img: &DynamicImage,
+        index: usize,
+        target: &mut RgbaImage,
+        left: u32,
+        top: u32,
+    ) {
+        let (width, height) = img.dimensions();
+        let (cell_width, cell_height) = self.get_cell_size(width, height);
+        let (slot_width, slot_height) = self.get_slot_size(width, height);
+        let column = index as u32 % self.columns;
+        let row = index as u32 / self.columns;
+
+        let shape = self.get_shape(index);
+        let padding = Self::get_padding(cell_width as f32, cell_height as f32).ceil() as u32;
+
+        for py in 0..slot_height {
+            for px in 0..slot_width {
+                let x = px as f32 - padding as f32 + 0.5;
+                let y = py as f32 - padding as f32 + 0.5;
+                if !shape.contains(cell_width as f32, cell_height as f32, x, y) {
+                    continue;
+                }
+                let source_x = (column * cell_width + px) as i64 - padding as i64;
+                let source_y = (row * cell_height + py) as i64 - padding as i64;
+                if source_x < 0
+                    || source_y < 0
+                    || source_x >= width as i64
+                    || source_y >= height as i64
+                {
+                    continue;
+                }
+                target.put_pixel(
+                    left + px,
+                    top + py,
+                    img.get_pixel(source_x as u32, source_y as u32),
+                );
+            }
+        }
+    }
+
+    /// white silhouette of every piece with a grey border, used to tint board slots, side by
+    /// side like `cut_sheet` for a picture of `cell_width` x `cell_height` cells, see
+    /// `get_sheet_rect` with that picture size for where a piece lies
+    pub fn outline_sheet(&self, cell_width: u32, cell_height: u32) -> RgbaImage {
+        let (slot_width, slot_height) =
+            self.get_slot_size(cell_width * self.columns, cell_height * self.rows);
+        let padding = Self::get_padding(cell_width as f32, cell_height as f32).ceil() as u32;
+        let (width, height) = (cell_width as f32, cell_height as f32);
+
+        let mut sheet = RgbaImage::new(slot_width * self.columns, slot_height * self.rows);
+        for index in 0..self.shapes.len() {
+            let shape = self.get_shape(index);
+            let left = index as u32 % self.columns * slot_width;
+            let top = index as u32 / self.columns * slot_height;
+            for py in 0..slot_height {
+                for px in 0..slot_width {
+                    let x = px as f32 - padding as f32 + 0.5;
+                    let y = py as f32 - padding as f32 + 0.5;
+                    if !shape.contains(width, height, x, y) {
+                        continue;
+                    }
+                    let border = !shape.contains(width, height, x - OUTLINE_WIDTH, y)
+                        || !shape.contains(width, height, x + OUTLINE_WIDTH, y)
+                        || !shape.contains(width, height, x, y - OUTLINE_WIDTH)
+                        || !shape.contains(width, height, x, y + OUTLINE_WIDTH);
+                    let color = if border { OUTLINE_COLOR } else { FILL_COLOR };
+                    sheet.put_pixel(left + px, top + py, color);
+                }
+            }
+        }
+        sheet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sides(shape: PieceShape) -> [Edge; 4] {
+        [shape.top, shape.right, shape.bottom, shape.left]
+    }
+
+    #[test]
+    fn same_seed_same_shapes() {
+        let cut = PuzzleCut::new(7, 5, 4);
+        let again = PuzzleCut::new(7, 5, 4);
+        let other = PuzzleCut::new(8, 5, 4);
+        let shapes = |cut: &PuzzleCut| (0..20).map(|i| cut.get_shape(i)).collect::<Vec<_>>();
+        assert_eq!(shapes(&cut), shapes(&again));
+        assert_ne!(shapes(&cut), shapes(&other));
+    }
+
+    #[test]
+    fn neighbours_fit_together() {
+        let (columns, rows) = (5, 4);
+        let cut = PuzzleCut::new(3, columns, rows);
+        for index in 0..(columns * rows) as usize {
+            let shape = cut.get_shape(index);
+            if !(index as u32 + 1).is_multiple_of(columns) {
+                let right = cut.get_shape(index + 1);
+                assert_ne!(shape.right, Edge::Flat);
+                assert_eq!(right.left, shape.right.opposite());
+            }
+            if (index as u32) < columns * (rows - 1) {
+                let below = cut.get_shape(index + columns as usize);
+                assert_ne!(shape.bottom, Edge::Flat);
+                assert_eq!(below.top, shape.bottom.opposite());
+            }
+        }
+    }
+
+    #[test]
+    fn border_edges_are_flat() {
+        let (columns, rows) = (5, 4);
+        let cut = PuzzleCut::new(3, columns, rows);
+        for index in 0..(columns * rows) as usize {
+            let [top, right, bottom, left] = sides(cut.get_shape(index));
+            let (column, row) = (index as u32 % columns, index as u32 / columns);
+            assert_eq!(top == Edge::Flat, row == 0);
+            assert_eq!(bottom == Edge::Flat, row == rows - 1);
+            assert_eq!(left == Edge::Flat, column == 0);
+            assert_eq!(right == Edge::Flat, column == columns - 1);
+        }
+    }
+
+    #[test]
+    fn cut_piece_is_opaque_inside_its_shape_only() {
+        let color = Rgba([10, 20, 30, 255]);
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(40, 40, color));
+        let cut = PuzzleCut::new(1, 2, 2);
+        let (cell_width, cell_height) = cut.get_cell_size(40, 40);
+        let padding = PuzzleCut::get_padding(cell_width as f32, cell_height as f32).ceil() as u32;
+
+        for index in 0..4 {
+            let piece = cut.cut_piece(&img, index);
+            let shape = cut.get_shape(index);
+            let (column, row) = (index as u32 % 2, index as u32 / 2);
+            assert_eq!(piece.dimensions(), (20 + 2 * padding, 20 + 2 * padding));
+            for (px, py, pixel) in piece.enumerate_pixels() {
+                let x = px as f32 - padding as f32 + 0.5;
+                let y = py as f32 - padding as f32 + 0.5;
+                let source_x = (column * cell_width + px) as i64 - padding as i64;
+                let source_y = (row * cell_height + py) as i64 - padding as i64;
+                let on_picture = (0..40).contains(&source_x) && (0..40).contains(&source_y);
+                let inside = on_picture && shape.contains(20., 20., x, y);
+                let expected = if inside { color } else { Rgba([0, 0, 0, 0]) };
+                assert_eq!(*pixel, expected, "piece {index} at ({px}, {py})");
+            }
+            // the cell itself is always whole away from the knobs
+            assert_eq!(piece.get_pixel(padding + 10, padding + 10)[3], 255);
+            assert_eq!(piece.get_pixel(0, 0)[3], 0);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::config::hint::HintLimit;
+use crate::config::seed::PuzzleSeed;
+use crate::play::board::{Board, BoardSlots};
+use crate::play::piece::Piece;
+use crate::play::result::Score;
+use crate::play::select::{SELECTED_TINT, Selected};
+use crate::play::{Above, Moving, Orientation};
+use bevy::prelude::*;
+use rand::Rng;
+
+const HINT_DURATION: f32 = 1.5;
+// the hinted slot or piece changes color this often
+const HINT_BLINK: f32 = 0.25;
+pub(super) const HINT_COLOR: Color = Color::srgb(1., 0.8, 0.);
+// random sequence of the puzzle seed picking a misplaced piece, each hint given starts its own
+const HINT_STREAM: u64 = 4;
+
+/// board slot or piece pointed at by a hint, for a short while
+#[derive(Component)]
+pub(super) struct Hinted(Timer);
+
+impl Hinted {
+    pub(super) fn is_lit(&self) -> bool {
+        ((self.0.elapsed_secs() / HINT_BLINK) as u32).is_multiple_of(2)
+    }
+}
+
+/// H shows the slots of the pieces in hand, or a piece lying in the wrong place
+#[allow(clippy::too_many_arguments)]
+pub(super) fn use_hint(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    limit: Res<HintLimit>,
+    mut score: ResMut<Score>,
+    held: Query<&Piece, With<Moving>>,
+    pieces: Query<(Entity, &Piece, &Orientation, Option<&Above>), Without<Moving>>,
+    boards: Query<&Board>,
+    slots: Res<BoardSlots>,
+    seed: Res<PuzzleSeed>,
+) {
+    if !keys.just_pressed(KeyCode::KeyH) {
+        return;
+    }
+    if score.hints >= limit.0 {
+        info!("no hint left");
+        return;
+    }
+
+    let mut targets: Vec<Entity> = held
+        .iter()
+        .filter_map(|piece| slots.get(piece.correct_index))
+        .collect();
+    if targets.is_empty() {
+        let misplaced: Vec<Entity> = pieces
+            .iter()
+            .filter(|(_, piece, orientation, above)| {
+                !orientation.is_correct()
+                    || above
+                        .and_then(|above| boards.get(above.0).ok())
+                        .is_none_or(|board| board.index != piece.correct_index)
+            })
+            .map(|(entity, ..)| entity)
+            .collect();
+        if misplaced.is_empty() {
+            return;
+        }
+        let mut rng = seed.get_rng(HINT_STREAM + (u64::from(score.hints) << 8));
+        targets.push(misplaced[rng.gen_range(0, misplaced.len())]);
+    }
+
+    score.hints += 1;
+    for target in targets {
+        commands
+            .entity(target)
+            .insert(Hinted(Timer::from_seconds(HINT_DURATION, TimerMode::Once)));
+    }
+}
+
+type HintedLook = (
+    Entity,
+    &'static mut Hinted,
+    Option<&'static mut Sprite>,
+    Has<Selected>,
+    Has<Board>,
+);
+
+/// blink hinted pieces, board slots blink in `draw_board_color`
+pub(super) fn draw_hints(mut commands: Commands, time: Res<Time>, mut hinted: Query<HintedLook>) {
+    for (entity, mut hint, sprite, selected, board) in hinted.iter_mut() {
+        hint.0.tick(time.delta());
+        let lit = hint.is_lit() && !hint.0.finished();
+        if let Some(mut sprite) = sprite.filter(|_| !board) {
+            sprite.color = match (lit, selected) {
+                (true, _) => HINT_COLOR,
+                (false, true) => SELECTED_TINT,
+                (false, false) => Color::WHITE,
+            };
+        }
+        if hint.0.finished() {
+            commands.entity(entity).remove::<Hinted>();
+        }
+    }
+}
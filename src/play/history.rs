@@ -0,0 +1,73 @@
+use crate::config::undo::UndoDepth;
+use crate::play::board::Board;
+use crate::play::save::{
+    RestoredPieceData, SavedPiece, SavedPieceData, restore_pieces, save_pieces,
+};
+use bevy::prelude::*;
+
+/// the table after every drop, oldest first; the last one is the table as it is now
+#[derive(Resource, Default)]
+pub(super) struct History {
+    past: Vec<Vec<SavedPiece>>,
+    future: Vec<Vec<SavedPiece>>,
+}
+
+/// a new puzzle starts a new history
+pub(super) fn start_history(
+    mut commands: Commands,
+    pieces: Query<SavedPieceData>,
+    boards: Query<&Board>,
+) {
+    commands.insert_resource(History {
+        past: vec![save_pieces(&pieces, &boards)],
+        future: vec![],
+    });
+}
+
+/// a pick and its drop are one step, whatever number of pieces moved
+pub(super) fn record_history(
+    mut history: ResMut<History>,
+    depth: Res<UndoDepth>,
+    pieces: Query<SavedPieceData>,
+    boards: Query<&Board>,
+) {
+    history.past.push(save_pieces(&pieces, &boards));
+    history.future.clear();
+    let excess = history.past.len().saturating_sub(depth.0 + 1);
+    history.past.drain(..excess);
+}
+
+/// ctrl+z puts the table back as it was before the last drop, ctrl+shift+z redoes it
+pub(super) fn undo_redo(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<History>,
+    mut pieces: Query<RestoredPieceData>,
+    boards: Query<(Entity, &Board)>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.just_pressed(KeyCode::KeyZ)
+    {
+        return;
+    }
+
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let Some(next) = history.future.pop() else {
+            return;
+        };
+        restore_pieces(&mut commands, &next, &mut pieces, &boards);
+        history.past.push(next);
+    } else {
+        if history.past.len() < 2 {
+            return;
+        }
+        let current = history.past.pop().unwrap();
+        history.future.push(current);
+        restore_pieces(
+            &mut commands,
+            history.past.last().unwrap(),
+            &mut pieces,
+            &boards,
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::config::total_pieces::TotalPieces;
+use crate::model::{Grid, Point};
+use crate::{PAINT_BOARD_HEIGHT, PAINT_BOARD_WIDTH};
+use bevy::prelude::*;
+
+/// grid and size of the board for the current picture, see `Grid` for the rules
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct BoardLayout {
+    grid: Grid,
+}
+
+impl BoardLayout {
+    pub fn new(total_pieces: &TotalPieces, image_width: u32, image_height: u32) -> Self {
+        let (columns, rows) = total_pieces.get_grid(image_width, image_height);
+
+        // fit the picture into the paint area, keeping its aspect ratio
+        let scale =
+            (PAINT_BOARD_WIDTH / image_width as f32).min(PAINT_BOARD_HEIGHT / image_height as f32);
+
+        BoardLayout {
+            grid: Grid::new(
+                columns,
+                rows,
+                image_width as f32 * scale,
+                image_height as f32 * scale,
+            ),
+        }
+    }
+
+    pub fn get_grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn get_columns(&self) -> u32 {
+        self.grid.get_columns()
+    }
+
+    pub fn get_rows(&self) -> u32 {
+        self.grid.get_rows()
+    }
+
+    pub fn get_count(&self) -> usize {
+        self.grid.get_count()
+    }
+
+    pub fn get_board_width(&self) -> f32 {
+        self.grid.get_width()
+    }
+
+    pub fn get_board_height(&self) -> f32 {
+        self.grid.get_height()
+    }
+
+    pub fn get_cell_width(&self) -> f32 {
+        self.grid.get_cell_width()
+    }
+
+    pub fn get_cell_height(&self) -> f32 {
+        self.grid.get_cell_height()
+    }
+
+    /// pixel size of a cell in the sheet of slot outlines, one pixel per board unit
+    pub fn get_outline_cell_size(&self) -> UVec2 {
+        UVec2::new(
+            self.get_cell_width().round().max(1.) as u32,
+            self.get_cell_height().round().max(1.) as u32,
+        )
+    }
+
+    pub fn is_edge(&self, index: usize) -> bool {
+        self.grid.is_edge(index)
+    }
+
+    pub fn get_radius_half(&self) -> f32 {
+        self.grid.get_radius_half()
+    }
+
+    pub fn get_slot_center(&self, index: usize) -> Vec2 {
+        to_vec2(self.grid.get_slot_center(index))
+    }
+
+    pub fn get_slot_at(&self, position: Vec2) -> Option<usize> {
+        self.grid.get_slot_at(to_point(position))
+    }
+
+    pub fn get_drop_slot(&self, position: Vec2, quarters: u8) -> Option<usize> {
+        self.grid.get_drop_slot(to_point(position), quarters)
+    }
+}
+
+pub(crate) fn to_point(position: Vec2) -> Point {
+    Point::new(position.x, position.y)
+}
+
+pub(crate) fn to_vec2(point: Point) -> Vec2 {
+    Vec2::new(point.x, point.y)
+}
@@ -1,12 +1,38 @@
 mod board;
+mod camera;
+pub mod cut;
+mod hint;
+mod history;
+pub mod layout;
 mod piece;
-mod result;
+pub mod pointer;
+mod preview;
+pub(crate) mod result;
+pub(crate) mod save;
+mod select;
+#[cfg(test)]
+mod tests;
+pub(crate) mod tray;
 
-use crate::config::total_pieces::TotalPieces;
+pub use piece::drag_plugin;
+
+use crate::config::rotation::RotationMode;
+use crate::config::seed::SeedEntry;
 use crate::play::board::{draw_board_color, setup_board};
-use crate::play::piece::{move_sprite, setup_piece};
-use crate::play::result::setup_result;
-use crate::{GameState, PAINT_BOARD_HEIGHT, PAINT_BOARD_WIDTH, despawn_screen};
+use crate::play::camera::{fit_all, fit_all_key, pan_camera, wheel_zooms, zoom_camera};
+use crate::play::hint::{draw_hints, use_hint};
+use crate::play::history::{History, record_history, start_history, undo_redo};
+use crate::play::layout::BoardLayout;
+use crate::play::piece::{check_piece_all_correct, move_sprite, rotate_pieces, setup_piece};
+use crate::play::pointer::{Cursor, track_cursor};
+use crate::play::preview::{adjust_preview, setup_preview};
+use crate::play::result::{render_score, setup_result, tick_score, update_status};
+use crate::play::save::{
+    RestoreGame, SaveFile, discard_save, not_solved, restore_game, save_game, save_on_exit,
+};
+use crate::play::select::{draw_selected, select_box};
+use crate::play::tray::{Sorted, sort_pieces};
+use crate::{GameState, despawn_screen};
 use bevy::app::{App, Update};
 use bevy::math::Vec2;
 use bevy::prelude::*;
@@ -14,8 +40,39 @@ use bevy::prelude::*;
 #[derive(Component)]
 struct Moving(Vec2);
 
+/// quarter turns counter-clockwise away from the correct orientation
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Orientation(u8);
+
+impl Orientation {
+    fn turn(&mut self, quarters: i8) {
+        self.0 = (self.0 as i8 + quarters).rem_euclid(4) as u8;
+    }
+
+    fn is_correct(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn get_angle(&self) -> f32 {
+        self.0 as f32 * std::f32::consts::FRAC_PI_2
+    }
+}
+
 type CorrectIndex = usize;
 
+/// textures of the current puzzle: the whole picture and the sheet of cut pieces
+#[derive(Resource)]
+pub struct PuzzleImage {
+    pub source: Handle<Image>,
+    pub sheet: Handle<Image>,
+    /// outline of every board slot, laid out like `sheet`, see `BoardLayout::get_outline_cell_size`
+    pub outlines: Handle<Image>,
+    /// size of the picture the pieces were cut from, in pixel
+    pub size: UVec2,
+    /// color group of each piece, pieces sorted by color share a tray with their group
+    pub color_groups: Vec<usize>,
+}
+
 #[derive(States, Default, Clone, Eq, Debug, Hash, PartialEq)]
 enum MoveState {
     #[default]
@@ -39,6 +96,15 @@ struct PreAbove(Entity);
 #[relationship_target(relationship = PreAbove)]
 struct PreUnder(Entity);
 
+/// pieces snapped together off the board, they move as one
+#[derive(Component, Debug)]
+#[relationship(relationship_target = Group)]
+struct InGroup(Entity);
+
+#[derive(Component, Debug)]
+#[relationship_target(relationship = InGroup)]
+struct Group(Vec<Entity>);
+
 #[derive(Event)]
 pub struct Success;
 
@@ -47,25 +113,89 @@ struct OnPlayScreen;
 
 pub fn play_plugin(app: &mut App) {
     app.init_state::<MoveState>()
-        .add_systems(OnEnter(GameState::Play), setup_board)
-        .add_systems(OnEnter(GameState::Play), setup_piece)
-        .add_systems(OnEnter(GameState::Play), setup_result)
+        .init_resource::<Cursor>()
+        .init_resource::<SaveFile>()
+        .add_systems(First, track_cursor)
+        .add_observer(check_piece_all_correct)
+        .add_observer(update_status)
+        .add_systems(
+            OnEnter(GameState::Play),
+            (
+                (setup_board, setup_piece),
+                restore_game.run_if(resource_exists::<RestoreGame>),
+                (fit_all, start_history),
+            )
+                .chain(),
+        )
+        .add_systems(
+            OnEnter(MoveState::Init),
+            (
+                save_game.run_if(not_solved),
+                record_history.run_if(resource_exists::<History>),
+            )
+                .run_if(in_state(GameState::Play)),
+        )
+        .add_event::<Sorted>()
+        .add_systems(
+            Update,
+            (
+                save_game.run_if(not_solved),
+                record_history.run_if(resource_exists::<History>),
+            )
+                .after(sort_pieces)
+                .run_if(on_event::<Sorted>),
+        )
+        .add_systems(
+            Update,
+            undo_redo.run_if(in_state(MoveState::Init).and(resource_exists::<History>)),
+        )
+        .add_systems(Last, save_on_exit.run_if(in_state(GameState::Play)))
+        .add_observer(discard_save)
+        .add_systems(OnEnter(GameState::Play), setup_result.before(restore_game))
+        .add_systems(OnEnter(GameState::Play), setup_preview)
+        .add_systems(Update, adjust_preview.run_if(in_state(GameState::Play)))
+        .add_systems(
+            Update,
+            (tick_score, render_score)
+                .chain()
+                .run_if(in_state(GameState::Play)),
+        )
         .add_systems(OnExit(GameState::Play), despawn_screen::<OnPlayScreen>)
-        .add_systems(Update, draw_board_color.run_if(in_state(GameState::Play)))
-        .add_systems(Update, move_sprite.run_if(in_state(MoveState::Move)));
+        .add_systems(
+            Update,
+            (use_hint, draw_hints, draw_board_color)
+                .chain()
+                .run_if(in_state(GameState::Play)),
+        )
+        .add_systems(Update, move_sprite.run_if(in_state(MoveState::Move)))
+        .add_systems(
+            Update,
+            (
+                (select_box, sort_pieces).run_if(in_state(MoveState::Init)),
+                draw_selected,
+            )
+                .chain()
+                .run_if(in_state(GameState::Play)),
+        )
+        .add_systems(
+            Update,
+            (zoom_camera.run_if(wheel_zooms), pan_camera, fit_all_key)
+                .before(move_sprite)
+                .run_if(in_state(GameState::Play)),
+        )
+        .add_systems(
+            Update,
+            rotate_pieces
+                .before(move_sprite)
+                .run_if(in_state(MoveState::Move))
+                .run_if(
+                    |rotation_mode: Res<RotationMode>, seed_entry: Res<SeedEntry>| {
+                        seed_entry.rotates(&rotation_mode)
+                    },
+                ),
+        );
 }
 
-fn get_correct_position(index: usize, total_pieces: &TotalPieces) -> Transform {
-    let width_index = index % total_pieces.get_width_count() as usize;
-    let height_index = total_pieces.get_height_count() as usize
-        - 1
-        - (index / total_pieces.get_width_count() as usize);
-
-    Transform::from_xyz(
-        total_pieces.get_side_length() / 2. + width_index as f32 * total_pieces.get_side_length()
-            - PAINT_BOARD_WIDTH / 2.,
-        total_pieces.get_side_length() / 2. + height_index as f32 * total_pieces.get_side_length()
-            - PAINT_BOARD_HEIGHT / 2.,
-        0.0,
-    )
+fn get_correct_position(index: usize, layout: &BoardLayout) -> Transform {
+    Transform::from_translation(layout.get_slot_center(index).extend(0.))
 }
@@ -1,68 +1,86 @@
-use crate::config::level::Levels;
-use crate::config::total_pieces::TotalPieces;
-use crate::play::board::Board;
+use crate::config::input_mode::InputMode;
+use crate::config::rotation::RotationMode;
+use crate::config::seed::{PuzzleSeed, SeedEntry};
+use crate::model::{PieceState, PuzzleModel};
+use crate::play::board::{Board, BoardSlots};
+use crate::play::cut::PuzzleCut;
+use crate::play::layout::{BoardLayout, to_point, to_vec2};
+use crate::play::pointer::Cursor;
+use crate::play::result::Score;
+use crate::play::select::{Selected, selecting, toggle_selected};
+use crate::play::tray::scatter_in_trays;
 use crate::play::{
-    Above, CorrectIndex, MoveState, Moving, OnPlayScreen, PreAbove, Success, Under,
-    get_correct_position,
+    Above, CorrectIndex, Group, InGroup, MoveState, Moving, OnPlayScreen, Orientation, PreAbove,
+    PuzzleImage, Success, Under, get_correct_position,
 };
-use bevy::asset::{Assets, RenderAssetUsages};
-use bevy::image::Image;
+use bevy::input::mouse::MouseWheel;
 use bevy::math::Vec2;
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
-use image::{DynamicImage, GenericImageView};
-use rand::{Rng, thread_rng};
-use std::mem::forget;
-use std::path::Path;
+use rand::Rng;
+use std::collections::HashSet;
+
+// pieces lie above the board and the ghost picture, moves only change x and y
+pub(super) const PIECE_Z: f32 = 1.;
+// random sequence of the puzzle seed turning the pieces
+const TURN_STREAM: u64 = 2;
 
 #[derive(Component)]
-#[require(Sprite, Transform)]
+#[require(Sprite, Transform, Orientation)]
 pub struct Piece {
     pub correct_index: CorrectIndex,
 }
 
 pub fn setup_piece(
     mut commands: Commands,
-    mut images: ResMut<Assets<Image>>,
-    total_pieces: Res<TotalPieces>,
-    level: Res<Levels>,
+    layout: Res<BoardLayout>,
+    puzzle_image: Res<PuzzleImage>,
+    cut: Res<PuzzleCut>,
+    rotation_mode: Res<RotationMode>,
+    seed_entry: Res<SeedEntry>,
+    seed: Res<PuzzleSeed>,
 ) {
-    let split_images = split_image(
-        level.current_level().get_path(),
-        total_pieces.get_width_count() as u32,
-        total_pieces.get_height_count() as u32,
-    )
-    .unwrap();
+    let (width, height) = (puzzle_image.size.x, puzzle_image.size.y);
+    let (cell_width, cell_height) = cut.get_cell_size(width, height);
+    let positions = scatter_in_trays(&mut commands, &layout, &seed);
+    let mut turns = seed.get_rng(TURN_STREAM);
 
     let mut all_correct_positions = vec![];
 
-    for (index, image) in split_images.into_iter().enumerate() {
-        let img = Image::from_dynamic(image, true, RenderAssetUsages::RENDER_WORLD);
-        let img_handle = images.add(img);
-
-        let mut sprite = Sprite::from_image(img_handle);
+    for (index, position) in positions.into_iter().enumerate() {
+        let rect = cut.get_sheet_rect(index, width, height);
+        let mut sprite = Sprite::from_image(puzzle_image.sheet.clone());
+        sprite.rect = Some(rect);
         sprite.custom_size = Some(Vec2::new(
-            total_pieces.get_side_length(),
-            total_pieces.get_side_length(),
+            layout.get_cell_width() * rect.width() / cell_width as f32,
+            layout.get_cell_height() * rect.height() / cell_height as f32,
         ));
-        let correct_position = get_correct_position(index, &total_pieces);
+        let correct_position = get_correct_position(index, &layout);
         all_correct_positions.push(correct_position);
+        let orientation = if seed_entry.rotates(&rotation_mode) {
+            Orientation(turns.gen_range(1, 4))
+        } else {
+            Orientation::default()
+        };
         commands
             .spawn((
                 Piece {
                     correct_index: index,
                 },
-                random_position(),
+                Transform::from_translation(position)
+                    .with_rotation(Quat::from_rotation_z(orientation.get_angle())),
+                orientation,
                 sprite,
                 Pickable::default(),
                 OnPlayScreen,
             ))
             .observe(chose_one_piece)
             .observe(chose_pieces)
+            .observe(drag_pieces)
+            .observe(drop_pieces)
+            .observe(toggle_selected)
             .observe(piece_picked)
             .observe(piece_unpicked);
     }
-    commands.add_observer(check_piece_all_correct);
 }
 
 #[derive(Event)]
@@ -72,70 +90,87 @@ struct Pick(bool, Vec2);
 struct Unpick;
 
 #[derive(Event)]
-struct PieceMatch;
+pub(super) struct PieceMatch;
 
+/// put down everything in hand, a group is released once, through any of its pieces
+fn release_pieces(
+    commands: &mut Commands,
+    picked: Query<(Entity, Option<&InGroup>), With<Moving>>,
+) {
+    let mut released_groups = vec![];
+    for (piece, in_group) in picked {
+        commands.entity(piece).remove::<Moving>();
+        if let Some(in_group) = in_group {
+            if released_groups.contains(&in_group.0) {
+                continue;
+            }
+            released_groups.push(in_group.0);
+        }
+        commands.trigger_targets(Unpick, piece);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn chose_pieces(
     click: Trigger<Pointer<Click>>,
     pieces: Query<&Piece, Without<Moving>>,
     mut commands: Commands,
     state: Res<State<MoveState>>,
     mut next_state: ResMut<NextState<MoveState>>,
-    picked: Query<Entity, With<Moving>>,
-    q_camera: Query<(&Camera, &GlobalTransform)>,
+    picked: Query<(Entity, Option<&InGroup>), With<Moving>>,
+    cursor: Res<Cursor>,
+    mut score: ResMut<Score>,
+    input_mode: Res<InputMode>,
+    keys: Res<ButtonInput<KeyCode>>,
 ) {
-    if click.button != PointerButton::Primary {
+    if click.button != PointerButton::Primary || *input_mode != InputMode::Click {
         return;
     }
 
-    let (camera, camera_transform) = q_camera.single().unwrap();
-
     match state.get() {
         MoveState::Init => {
-            if pieces.get(click.target).is_ok() {
-                let world_position = camera
-                    .viewport_to_world(camera_transform, click.pointer_location.position)
-                    .unwrap()
-                    .origin
-                    .truncate();
-
-                commands.trigger_targets(Pick(true, world_position), click.target)
+            if selecting(&keys) {
+                return;
+            }
+            if let (Ok(_), Some(world_position)) = (pieces.get(click.target), cursor.0) {
+                commands.trigger_targets(Pick(true, world_position), click.target);
+                score.moves += 1;
             }
             next_state.set(MoveState::Move);
         }
         MoveState::Move => {
-            for piece in picked {
-                commands.entity(piece).remove::<Moving>();
-                commands.trigger_targets(Unpick, piece);
-            }
+            release_pieces(&mut commands, picked);
+            score.moves += 1;
 
             next_state.set(MoveState::Init);
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn chose_one_piece(
     click: Trigger<Pointer<Click>>,
     pieces: Query<&Piece, Without<Moving>>,
     mut commands: Commands,
     state: Res<State<MoveState>>,
     mut next_state: ResMut<NextState<MoveState>>,
-    q_camera: Query<(&Camera, &GlobalTransform)>,
+    cursor: Res<Cursor>,
+    mut score: ResMut<Score>,
+    input_mode: Res<InputMode>,
+    keys: Res<ButtonInput<KeyCode>>,
 ) {
-    if click.button != PointerButton::Secondary {
+    if click.button != PointerButton::Secondary
+        || *input_mode != InputMode::Click
+        || selecting(&keys)
+    {
         return;
     }
-    let (camera, camera_transform) = q_camera.single().unwrap();
 
     match state.get() {
         MoveState::Init => {
-            if pieces.get(click.target).is_ok() {
-                let world_position = camera
-                    .viewport_to_world(camera_transform, click.pointer_location.position)
-                    .unwrap()
-                    .origin
-                    .truncate();
-
-                commands.trigger_targets(Pick(false, world_position), click.target)
+            if let (Ok(_), Some(world_position)) = (pieces.get(click.target), cursor.0) {
+                commands.trigger_targets(Pick(false, world_position), click.target);
+                score.moves += 1;
             }
             next_state.set(MoveState::Move);
         }
@@ -143,26 +178,104 @@ fn chose_one_piece(
     }
 }
 
+/// drag mode: pressing on a piece and moving takes it, the primary button takes the pieces
+/// around on the board too, like a left click
+#[allow(clippy::too_many_arguments)]
+fn drag_pieces(
+    drag: Trigger<Pointer<DragStart>>,
+    pieces: Query<&Piece, Without<Moving>>,
+    mut commands: Commands,
+    state: Res<State<MoveState>>,
+    mut next_state: ResMut<NextState<MoveState>>,
+    cursor: Res<Cursor>,
+    mut score: ResMut<Score>,
+    input_mode: Res<InputMode>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if *input_mode != InputMode::Drag
+        || *state.get() != MoveState::Init
+        || pieces.get(drag.target).is_err()
+        || selecting(&keys)
+    {
+        return;
+    }
+    let pick_around = match drag.button {
+        PointerButton::Primary => true,
+        PointerButton::Secondary => false,
+        PointerButton::Middle => return,
+    };
+
+    let Some(world_position) = cursor.0 else {
+        return;
+    };
+    commands.trigger_targets(Pick(pick_around, world_position), drag.target);
+    score.moves += 1;
+    next_state.set(MoveState::Move);
+}
+
+/// drag mode: releasing the button drops what was taken
+fn drop_pieces(
+    drag: Trigger<Pointer<DragEnd>>,
+    mut commands: Commands,
+    state: Res<State<MoveState>>,
+    mut next_state: ResMut<NextState<MoveState>>,
+    picked: Query<(Entity, Option<&InGroup>), With<Moving>>,
+    mut score: ResMut<Score>,
+    input_mode: Res<InputMode>,
+) {
+    if *input_mode != InputMode::Drag
+        || *state.get() != MoveState::Move
+        || drag.button == PointerButton::Middle
+    {
+        return;
+    }
+
+    release_pieces(&mut commands, picked);
+    score.moves += 1;
+    next_state.set(MoveState::Init);
+}
+
+#[allow(clippy::too_many_arguments)]
 fn piece_picked(
     pick: Trigger<Pick>,
     above: Query<&Above>,
     mut commands: Commands,
-    pieces: Query<&Transform, (With<Piece>, Without<Moving>)>,
-    boards: Query<(Entity, &Board)>,
-    unders: Query<&Under>,
-    total_pieces: Res<TotalPieces>,
+    pieces: Query<PieceOnTable>,
+    boards: Query<&Board>,
+    in_group: Query<&InGroup>,
+    groups: Query<&Group>,
+    selected: Query<Entity, (With<Selected>, Without<Moving>)>,
+    placed: Query<Entity, (With<Piece>, With<Above>)>,
+    layout: Res<BoardLayout>,
 ) {
-    let piece_transform = pieces.get(pick.target());
-    if piece_transform.is_err() {
-        return;
-    }
-    let piece_transform = piece_transform.unwrap();
+    let piece_transform = match pieces.get(pick.target()) {
+        Ok((_, _, transform, .., false)) => transform,
+        _ => return,
+    };
 
     commands.entity(pick.target()).insert(Moving(Vec2::new(
         piece_transform.translation.x - pick.1.x,
         piece_transform.translation.y - pick.1.y,
     )));
 
+    // the whole group follows
+    if let Ok(group) = in_group.get(pick.target()).and_then(|g| groups.get(g.0)) {
+        for member in group.0.iter() {
+            if *member != pick.target() {
+                commands.trigger_targets(Pick(pick.0, pick.1), *member);
+            }
+        }
+    }
+
+    // and so does the selection the piece belongs to
+    if selected.contains(pick.target()) {
+        for other in selected.iter() {
+            if other != pick.target() {
+                commands.trigger_targets(Pick(pick.0, pick.1), other);
+            }
+        }
+    }
+
     let above = above.get(pick.target());
     if above.is_err() {
         return;
@@ -171,29 +284,15 @@ fn piece_picked(
     let above = above.unwrap();
 
     if pick.0 {
-        // pick around
-        let board = above.0;
-        let board = boards.get(board).unwrap();
-
-        let mut around_index = vec![
-            board.1.index + 1,
-            board.1.index + total_pieces.get_columns() as usize,
-        ];
-        if board.1.index >= 1 {
-            around_index.push(board.1.index - 1);
-        }
-        if board.1.index >= total_pieces.get_columns() as usize {
-            around_index.push(board.1.index - total_pieces.get_columns() as usize)
-        }
-
-        for index in around_index.into_iter() {
-            for board in boards {
-                if board.1.index == index {
-                    if let Ok(under) = unders.get(board.0) {
-                        commands.trigger_targets(Pick(true, pick.1), under.0)
-                    }
-                    break;
-                }
+        // pick around, only pieces on the board can be around
+        let (model, entities) = get_model(
+            pieces.iter_many(placed.iter()),
+            |slot| boards.get(slot).ok().map(|board| board.index),
+            &layout,
+        );
+        if let Some(id) = entities.iter().position(|entity| *entity == pick.target()) {
+            for other in model.get_around(id) {
+                commands.trigger_targets(Pick(true, pick.1), entities[other]);
             }
         }
     }
@@ -201,126 +300,322 @@ fn piece_picked(
     commands.entity(pick.target()).insert(PreAbove(above.0));
 }
 
+#[allow(clippy::too_many_arguments)]
 fn piece_unpicked(
     unpick: Trigger<Unpick>,
-    pre_above: Query<&PreAbove>,
     mut commands: Commands,
-    mut pieces: Query<(&Piece, &mut Transform)>,
+    mut pieces: Query<PieceOnTable>,
+    placed: Query<Entity, (With<Piece>, With<Above>)>,
     boards: Query<(&Board, &Transform), Without<Piece>>,
+    groups: Query<&Group>,
+    slots: Res<BoardSlots>,
+    layout: Res<BoardLayout>,
 ) {
-    if let Ok(pre_above) = pre_above.get(unpick.target()) {
-        let (_, mut piece_transform) = pieces.get_mut(unpick.target()).unwrap();
-        let (_, box_transform) = boards.get(pre_above.0).unwrap();
-        piece_transform.translation.x = box_transform.translation.x;
-        piece_transform.translation.y = box_transform.translation.y;
-
-        commands.entity(unpick.target()).remove::<PreAbove>();
-        commands.entity(unpick.target()).insert(Above(pre_above.0));
+    let released = match pieces.get(unpick.target()) {
+        Ok((.., Some(in_group), _)) => groups.get(in_group.0).unwrap().0.clone(),
+        Ok(_) => vec![unpick.target()],
+        Err(_) => return,
+    };
+    for entity in released.iter() {
+        commands.entity(*entity).remove::<PreAbove>();
+    }
+
+    // where a drop lands only depends on the released pieces and the ones on the board
+    let (model, entities) = get_model(
+        pieces.iter_many(released.iter().copied().chain(placed.iter())),
+        |slot| boards.get(slot).ok().map(|(board, _)| board.index),
+        &layout,
+    );
+    let ids: Vec<usize> = released
+        .iter()
+        .filter_map(|released| entities.iter().position(|entity| entity == released))
+        .collect();
+    if let Some(placement) = model.place_group(&ids) {
+        let delta = to_vec2(placement.delta);
+        for entity in released.iter() {
+            let (_, _, mut transform, ..) = pieces.get_mut(*entity).unwrap();
+            transform.translation += delta.extend(0.);
+        }
+        for (id, cell) in placement.placed {
+            let Some(slot) = slots.get(cell) else {
+                continue;
+            };
+            let (_, _, mut transform, ..) = pieces.get_mut(entities[id]).unwrap();
+            let center = layout.get_slot_center(cell);
+            transform.translation.x = center.x;
+            transform.translation.y = center.y;
+            commands.entity(entities[id]).insert(Above(slot));
+        }
+        commands.trigger(PieceMatch);
+        return;
+    }
+
+    if snap_to_neighbours(
+        &released,
+        &mut commands,
+        &mut pieces,
+        &boards,
+        &groups,
+        &layout,
+    ) {
         commands.trigger(PieceMatch);
     }
 }
 
-fn check_piece_all_correct(
-    _: Trigger<PieceMatch>,
-    above: Query<(&Above, Entity)>,
-    pieces: Query<&Piece>,
-    boards: Query<&Board>,
-    total_pieces: Res<TotalPieces>,
-    mut commands: Commands,
-) {
-    if above.iter().len() != total_pieces.get_value() as usize {
-        return;
+type PieceOnTable = (
+    Entity,
+    &'static Piece,
+    &'static mut Transform,
+    &'static Orientation,
+    Option<&'static Above>,
+    Option<&'static InGroup>,
+    Has<Moving>,
+);
+
+type PieceInModel = (
+    Entity,
+    &'static Piece,
+    &'static Transform,
+    &'static Orientation,
+    Option<&'static Above>,
+    Option<&'static InGroup>,
+    Has<Moving>,
+);
+
+/// the rules' view of the pieces, with the entity of each model piece;
+/// `slot_of` tells the board cell of a slot entity
+fn get_model<'a>(
+    pieces: impl Iterator<
+        Item = (
+            Entity,
+            &'a Piece,
+            &'a Transform,
+            &'a Orientation,
+            Option<&'a Above>,
+            Option<&'a InGroup>,
+            bool,
+        ),
+    >,
+    slot_of: impl Fn(Entity) -> Option<usize>,
+    layout: &BoardLayout,
+) -> (PuzzleModel, Vec<Entity>) {
+    let mut entities = vec![];
+    let mut states = vec![];
+    for (entity, piece, transform, orientation, above, in_group, moving) in pieces {
+        entities.push(entity);
+        states.push(PieceState {
+            index: piece.correct_index,
+            position: to_point(transform.translation.truncate()),
+            quarters: orientation.0,
+            slot: above.and_then(|above| slot_of(above.0)),
+            group: in_group.map(|in_group| in_group.0.index() as usize),
+            in_hand: moving,
+        });
     }
+    (PuzzleModel::new(*layout.get_grid(), states), entities)
+}
 
-    for (above, entity) in above {
-        let piece = pieces.get(entity).unwrap();
-        let board = boards.get(above.0).unwrap();
+/// move the released pieces onto a matching neighbour lying on the table and merge them
+/// into one group, returns whether anything snapped
+fn snap_to_neighbours(
+    released: &[Entity],
+    commands: &mut Commands,
+    pieces: &mut Query<PieceOnTable>,
+    boards: &Query<(&Board, &Transform), Without<Piece>>,
+    groups: &Query<&Group>,
+    layout: &BoardLayout,
+) -> bool {
+    let (model, entities) = get_model(
+        pieces.iter(),
+        |slot| boards.get(slot).ok().map(|(board, _)| board.index),
+        layout,
+    );
+    let ids: Vec<usize> = released
+        .iter()
+        .filter_map(|released| entities.iter().position(|entity| entity == released))
+        .collect();
+    let Some(snap) = model.find_snap(&ids) else {
+        return false;
+    };
+
+    let delta = to_vec2(snap.delta);
+    for entity in released {
+        let (_, _, mut transform, ..) = pieces.get_mut(*entity).unwrap();
+        transform.translation += delta.extend(0.);
+    }
 
-        if piece.correct_index != board.index {
-            return;
+    let joined: Vec<(Entity, Option<Entity>)> = snap
+        .joined
+        .iter()
+        .map(|id| {
+            let (entity, .., in_group, _) = pieces.get(entities[*id]).unwrap();
+            (entity, in_group.map(|g| g.0))
+        })
+        .collect();
+    let released_group = pieces.get(released[0]).unwrap().5.map(|g| g.0);
+    let group = released_group
+        .or_else(|| {
+            joined
+                .iter()
+                .find_map(|(_, neighbour_group)| *neighbour_group)
+        })
+        .unwrap_or_else(|| commands.spawn(OnPlayScreen).id());
+
+    let mut members = released.to_vec();
+    let mut merged_groups = vec![];
+    for (neighbour_entity, neighbour_group) in joined {
+        match neighbour_group {
+            Some(neighbour_group) if neighbour_group != group => {
+                if !merged_groups.contains(&neighbour_group) {
+                    merged_groups.push(neighbour_group);
+                    members.extend(groups.get(neighbour_group).unwrap().0.iter());
+                }
+            }
+            Some(_) => {}
+            None => members.push(neighbour_entity),
         }
     }
-    commands.trigger(Success);
+
+    for member in members {
+        commands.entity(member).insert(InGroup(group));
+    }
+    for merged_group in merged_groups {
+        commands.entity(merged_group).despawn();
+    }
+    true
 }
 
-pub fn move_sprite(
-    mut pieces: Query<(&mut Transform, &Moving, Entity), Without<Board>>,
+pub(super) fn check_piece_all_correct(
+    _: Trigger<PieceMatch>,
+    pieces: Query<PieceInModel>,
+    boards: Query<&Board>,
+    layout: Res<BoardLayout>,
     mut commands: Commands,
-    q_window: Query<&Window, With<PrimaryWindow>>,
-    q_camera: Query<(&Camera, &GlobalTransform)>,
-    mut boards_not_under_others: Query<(&Transform, Entity), (With<Board>, Without<Under>)>,
-    pre_above: Query<Entity, (With<PreAbove>)>,
-    total_pieces: Res<TotalPieces>,
 ) {
-    let (camera, camera_transform) = q_camera.single().unwrap();
-    let window = q_window.single().unwrap();
+    let (model, _) = get_model(
+        pieces.iter(),
+        |slot| boards.get(slot).ok().map(|board| board.index),
+        &layout,
+    );
+    if model.is_solved() {
+        commands.trigger(Success);
+    }
+}
 
-    let world_position = window
-        .cursor_position()
-        .map(|cursor| camera.viewport_to_world(camera_transform, cursor).unwrap())
-        .map(|ray| ray.origin.truncate());
+type PieceInHand = (
+    &'static mut Transform,
+    &'static Moving,
+    &'static Orientation,
+    Entity,
+    Option<&'static PreAbove>,
+);
 
-    if world_position.is_none() {
+pub fn move_sprite(
+    mut pieces: Query<PieceInHand, Without<Board>>,
+    mut commands: Commands,
+    cursor: Res<Cursor>,
+    free_slots: Query<(), (With<Board>, Without<Under>)>,
+    slots: Res<BoardSlots>,
+    layout: Res<BoardLayout>,
+) {
+    let Some(world_position) = cursor.0 else {
         return;
-    }
+    };
 
-    let world_position = world_position.unwrap();
+    let mut candidates = vec![];
+    for (mut current_position, moving, orientation, piece_entity, pre_above) in pieces.iter_mut() {
+        current_position.translation.x = world_position.x + moving.0.x;
+        current_position.translation.y = world_position.y + moving.0.y;
 
-    // remove all pre above relation
-    for piece_entity in pre_above.iter() {
-        commands.entity(piece_entity).remove::<PreAbove>();
+        // only the free slot under the piece can take it
+        let candidate = layout
+            .get_drop_slot(current_position.translation.truncate(), orientation.0)
+            .and_then(|index| slots.get(index))
+            .filter(|slot| free_slots.contains(*slot));
+        candidates.push((
+            piece_entity,
+            pre_above.map(|pre_above| pre_above.0),
+            candidate,
+        ));
     }
 
-    for (mut current_position, moving, piece_entity) in pieces.iter_mut() {
-        current_position.translation.x = world_position.x + moving.0.x;
-        current_position.translation.y = world_position.y + moving.0.y;
-        for (board_transform, board_entity) in boards_not_under_others.iter_mut() {
-            if close_correct_position(&current_position, board_transform, &total_pieces) {
-                commands.entity(piece_entity).insert(PreAbove(board_entity));
-            }
+    // pieces carried apart can be over the same slot, it stays with the piece already over it
+    // or goes to the first one; slots are let go before they are taken again
+    let mut taken: HashSet<Entity> = candidates
+        .iter()
+        .filter(|(_, current, candidate)| current.is_some() && current == candidate)
+        .filter_map(|(_, current, _)| *current)
+        .collect();
+    let mut preselected = vec![];
+    for (piece_entity, current, candidate) in candidates {
+        let candidate = candidate.filter(|slot| current == Some(*slot) || taken.insert(*slot));
+        if current == candidate {
+            continue;
+        }
+        if current.is_some() {
+            commands.entity(piece_entity).remove::<PreAbove>();
+        }
+        if let Some(slot) = candidate {
+            preselected.push((piece_entity, PreAbove(slot)));
         }
     }
+    commands.insert_batch(preselected);
 }
 
-fn close_correct_position(
-    current: &Transform,
-    correct_position: &Transform,
-    total_pieces: &TotalPieces,
-) -> bool {
-    let delta_x = current.translation.x - correct_position.translation.x;
-    let delta_y = current.translation.y - correct_position.translation.y;
-    if delta_x * delta_x + delta_y * delta_y < total_pieces.get_radius_half() {
-        return true;
+/// the board slots of the layout with every piece in hand above them, keeping their place
+/// relative to each other while the cursor drags them; the setup of `drag_plugin`
+fn spawn_hand(mut commands: Commands, layout: Res<BoardLayout>) {
+    let slots = (0..layout.get_count())
+        .map(|index| {
+            let slot = get_correct_position(index, &layout);
+            commands.spawn((slot, Board { index })).id()
+        })
+        .collect();
+    commands.insert_resource(BoardSlots(slots));
+
+    for index in 0..layout.get_count() {
+        commands.spawn((
+            Piece {
+                correct_index: index,
+            },
+            Transform::from_xyz(0., 0., PIECE_Z),
+            Moving(layout.get_slot_center(index) * 0.5),
+        ));
     }
-    false
 }
 
-fn split_image<P: AsRef<Path>>(
-    image_path: P,
-    width_count: u32,
-    height_count: u32,
-) -> Result<Vec<DynamicImage>, image::ImageError> {
-    let img = image::open(image_path)?;
-
-    let (width, height) = img.dimensions();
-
-    let sub_width = width / width_count;
-    let sub_height = height / height_count;
-
-    let mut sub_images = Vec::new();
-
-    for y in 0..height_count {
-        for x in 0..width_count {
-            let sub_img = img.crop_imm(x * sub_width, y * sub_height, sub_width, sub_height);
+/// pieces of the `BoardLayout` resource in hand, dragged by `Cursor` with nothing else of the
+/// game running, for `benches/drag.rs` to step without a window
+pub fn drag_plugin(app: &mut App) {
+    app.init_resource::<Cursor>()
+        .add_systems(Startup, spawn_hand)
+        .add_systems(Update, move_sprite);
+}
 
-            sub_images.push(sub_img);
-        }
+/// turn the pieces in hand a quarter around the cursor, with the wheel or R
+pub fn rotate_pieces(
+    mut wheel: EventReader<MouseWheel>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut pieces: Query<(&mut Transform, &mut Moving, &mut Orientation)>,
+) {
+    // horizontal scrolling does not turn, a burst of events is taken whole turns off
+    let mut quarters: i32 = wheel
+        .read()
+        .filter(|event| event.y != 0.)
+        .map(|event| event.y.signum() as i32)
+        .sum();
+    if keys.just_pressed(KeyCode::KeyR) {
+        quarters += 1;
+    }
+    let quarters = quarters.rem_euclid(4) as i8;
+    if quarters == 0 {
+        return;
     }
 
-    Ok(sub_images)
-}
-
-fn random_position() -> Transform {
-    let mut rng = thread_rng();
-    Transform::from_xyz(rng.gen_range(-800., 800.), rng.gen_range(-500., 500.), 0.)
+    let angle = quarters as f32 * std::f32::consts::FRAC_PI_2;
+    for (mut transform, mut moving, mut orientation) in pieces.iter_mut() {
+        moving.0 = Vec2::from_angle(angle).rotate(moving.0);
+        orientation.turn(quarters);
+        transform.rotation = Quat::from_rotation_z(orientation.get_angle());
+    }
 }
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// pointer position on the table, in world coordinates; follows the window cursor, a run
+/// without a window sets it by hand
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct Cursor(pub Option<Vec2>);
+
+/// read the window cursor through the camera, `Cursor` is left alone without a window
+pub(super) fn track_cursor(
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+    mut cursor: ResMut<Cursor>,
+) {
+    let (Ok(window), Ok((camera, camera_transform))) = (q_window.single(), q_camera.single())
+    else {
+        return;
+    };
+    let position = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world_2d(camera_transform, position).ok());
+    cursor.set_if_neq(Cursor(position));
+}
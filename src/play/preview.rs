@@ -0,0 +1,91 @@
+use crate::config::preview::{GhostOpacity, PreviewModes};
+use crate::config::total_pieces::TotalPieces;
+use crate::play::layout::BoardLayout;
+use crate::play::{OnPlayScreen, PuzzleImage};
+use bevy::prelude::*;
+
+// between the board slots and the pieces, see `PIECE_Z`
+const GHOST_Z: f32 = 0.5;
+const GHOST_STEP: f32 = 0.05;
+const GHOST_MIN: f32 = 0.05;
+const GHOST_MAX: f32 = 0.8;
+const PREVIEW_WIDTH: f32 = 240.;
+
+/// faint picture lying on the board
+#[derive(Component)]
+pub(super) struct Ghost;
+
+/// small picture in the top left corner of the window
+#[derive(Component)]
+pub(super) struct PreviewWindow;
+
+pub(super) fn setup_preview(
+    mut commands: Commands,
+    preview_modes: Res<PreviewModes>,
+    total_pieces: Res<TotalPieces>,
+    opacity: Res<GhostOpacity>,
+    puzzle_image: Res<PuzzleImage>,
+    layout: Res<BoardLayout>,
+) {
+    let preview_mode = preview_modes.get(&total_pieces);
+    if preview_mode.shows_ghost() {
+        let mut sprite = Sprite::from_image(puzzle_image.source.clone());
+        sprite.custom_size = Some(Vec2::new(
+            layout.get_board_width(),
+            layout.get_board_height(),
+        ));
+        sprite.color = Color::WHITE.with_alpha(opacity.0);
+        commands.spawn((
+            sprite,
+            Transform::from_xyz(0., 0., GHOST_Z),
+            Pickable::IGNORE,
+            Ghost,
+            OnPlayScreen,
+        ));
+    }
+
+    if preview_mode.shows_window() {
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                left: Val::Px(5.0),
+                width: Val::Px(PREVIEW_WIDTH),
+                height: Val::Px(
+                    PREVIEW_WIDTH * layout.get_board_height() / layout.get_board_width(),
+                ),
+                ..default()
+            },
+            ImageNode::new(puzzle_image.source.clone()),
+            Pickable::IGNORE,
+            PreviewWindow,
+            OnPlayScreen,
+        ));
+    }
+}
+
+/// P shows or hides the corner picture, [ and ] fade the ghost out or in
+pub(super) fn adjust_preview(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut opacity: ResMut<GhostOpacity>,
+    mut ghosts: Query<&mut Sprite, With<Ghost>>,
+    mut windows: Query<&mut Visibility, With<PreviewWindow>>,
+) {
+    if keys.just_pressed(KeyCode::KeyP) {
+        for mut visibility in windows.iter_mut() {
+            visibility.toggle_inherited_hidden();
+        }
+    }
+
+    let step = if keys.just_pressed(KeyCode::BracketLeft) {
+        -GHOST_STEP
+    } else if keys.just_pressed(KeyCode::BracketRight) {
+        GHOST_STEP
+    } else {
+        return;
+    };
+    opacity.0 = (opacity.0 + step).clamp(GHOST_MIN, GHOST_MAX);
+    for mut sprite in ghosts.iter_mut() {
+        sprite.color = Color::WHITE.with_alpha(opacity.0);
+    }
+}
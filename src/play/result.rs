@@ -1,14 +1,73 @@
 use crate::GameState;
+use crate::config::seed::{PuzzleSeed, SeedEntry};
+use crate::play::layout::BoardLayout;
+use crate::play::save::RestoreGame;
 use crate::play::{OnPlayScreen, Success};
+use crate::stats::format_time;
 use bevy::asset::AssetServer;
 use bevy::color::Color;
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+// points lost per second, per pick or drop and per hint
+const SECOND_PENALTY: f32 = 1.;
+const MOVE_PENALTY: u32 = 5;
+const HINT_PENALTY: u32 = 50;
+const POINTS_PER_PIECE: u32 = 100;
+
+/// time, moves and hints of the current puzzle
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct Score {
+    pub elapsed: f32,
+    pub moves: u32,
+    pub hints: u32,
+}
+
+impl Score {
+    /// every piece is worth some points, time, moves and hints take them away
+    pub fn get_points(&self, pieces: usize) -> u32 {
+        (pieces as u32 * POINTS_PER_PIECE)
+            .saturating_sub((self.elapsed * SECOND_PENALTY) as u32)
+            .saturating_sub(self.moves * MOVE_PENALTY)
+            .saturating_sub(self.hints * HINT_PENALTY)
+    }
+
+    pub fn get_text(&self, pieces: usize) -> String {
+        let hints = if self.hints > 0 {
+            format!("  hints {}", self.hints)
+        } else {
+            String::new()
+        };
+        format!(
+            "{}  moves {}{hints}  score {}",
+            format_time(self.elapsed),
+            self.moves,
+            self.get_points(pieces)
+        )
+    }
+}
 
 #[derive(Component)]
-struct Result;
-pub fn setup_result(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub(super) struct Result;
+pub fn setup_result(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    restore: Option<Res<RestoreGame>>,
+    seed: Res<PuzzleSeed>,
+    seed_entry: Res<SeedEntry>,
+) {
+    let score = match restore {
+        Some(restore) => Score {
+            elapsed: restore.0.elapsed,
+            moves: restore.0.moves,
+            hints: restore.0.hints,
+        },
+        None => Score::default(),
+    };
+    commands.insert_resource(score);
+
     commands.spawn((
-        Text::new("come on!"),
+        Text::new(""),
         TextFont {
             font: asset_server.load("fonts/FiraSans-Bold.ttf"),
             font_size: 33.0,
@@ -24,10 +83,53 @@ pub fn setup_result(mut commands: Commands, asset_server: Res<AssetServer>) {
         Result,
         OnPlayScreen,
     ));
-    commands.add_observer(update_status);
+
+    // the seed is shown so a puzzle can be played again or shared
+    let seed_text = if seed_entry.daily {
+        format!("daily puzzle, seed {}", seed.0)
+    } else {
+        format!("seed {}", seed.0)
+    };
+    commands.spawn((
+        Text::new(seed_text),
+        TextFont {
+            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.4, 0.4, 0.4)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            right: Val::Px(5.0),
+            ..default()
+        },
+        OnPlayScreen,
+    ));
+}
+
+/// the clock only runs while the window has focus
+pub(super) fn tick_score(
+    mut score: ResMut<Score>,
+    time: Res<Time>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+) {
+    if q_window.iter().any(|window| window.focused) {
+        score.elapsed += time.delta_secs();
+    }
+}
+
+pub(super) fn render_score(
+    score: Res<Score>,
+    layout: Res<BoardLayout>,
+    mut result: Query<&mut Text, With<Result>>,
+) {
+    for mut text in result.iter_mut() {
+        *text = Text::new(score.get_text(layout.get_count()));
+    }
 }
 
-fn update_status(
+pub(super) fn update_status(
     _: Trigger<Success>,
     mut result: Query<(&mut Text, &mut TextColor), With<Result>>,
     mut state: ResMut<NextState<GameState>>,
@@ -0,0 +1,259 @@
+use crate::GameState;
+use crate::config::level::Levels;
+use crate::config::rotation::RotationMode;
+use crate::config::seed::SeedEntry;
+use crate::config::total_pieces::TotalPieces;
+use crate::play::board::Board;
+use crate::play::cut::PuzzleCut;
+use crate::play::piece::Piece;
+use crate::play::result::Score;
+use crate::play::{Above, InGroup, Moving, OnPlayScreen, Orientation, PreAbove, Success};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SAVE_PATH: &str = "save.ron";
+
+/// where the unfinished puzzle is kept
+#[derive(Resource, Debug, Clone)]
+pub struct SaveFile(pub PathBuf);
+
+impl Default for SaveFile {
+    fn default() -> Self {
+        SaveFile(PathBuf::from(SAVE_PATH))
+    }
+}
+
+/// everything needed to rebuild an unfinished puzzle
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SaveGame {
+    pub level: String,
+    pub total_pieces: TotalPieces,
+    pub rotation: bool,
+    pub seed: u64,
+    #[serde(default)]
+    pub elapsed: f32,
+    #[serde(default)]
+    pub moves: u32,
+    #[serde(default)]
+    pub hints: u32,
+    pub pieces: Vec<SavedPiece>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedPiece {
+    pub correct_index: usize,
+    pub x: f32,
+    pub y: f32,
+    pub orientation: u8,
+    /// index of the board slot the piece lies on
+    pub above: Option<usize>,
+    /// pieces sharing a number snapped together
+    pub group: Option<usize>,
+}
+
+impl SaveGame {
+    pub fn exists(path: &Path) -> bool {
+        path.exists()
+    }
+
+    pub fn load(path: &Path) -> Result<SaveGame, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        ron::from_str(&content).map_err(|e| e.to_string())
+    }
+
+    fn write(&self, path: &Path) -> Result<(), String> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+        fs::write(path, content).map_err(|e| e.to_string())
+    }
+
+    fn discard(path: &Path) {
+        if let Err(e) = fs::remove_file(path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("can not remove {}: {e}", path.display());
+        }
+    }
+}
+
+pub(super) type SavedPieceData = (
+    &'static Piece,
+    &'static Transform,
+    &'static Orientation,
+    Option<&'static Above>,
+    Option<&'static InGroup>,
+);
+
+/// save read by the config screen, consumed when the play screen is built
+#[derive(Resource)]
+pub struct RestoreGame(pub SaveGame);
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn save_game(
+    save_file: Res<SaveFile>,
+    level: Res<Levels>,
+    total_pieces: Res<TotalPieces>,
+    rotation_mode: Res<RotationMode>,
+    seed_entry: Res<SeedEntry>,
+    cut: Res<PuzzleCut>,
+    score: Res<Score>,
+    pieces: Query<SavedPieceData>,
+    boards: Query<&Board>,
+) {
+    // nothing to save without a level, the puzzle could not be loaded again
+    let Some(level) = level.current_level() else {
+        return;
+    };
+    let save = SaveGame {
+        level: level.get_path().to_string(),
+        total_pieces: *total_pieces,
+        rotation: seed_entry.rotates(&rotation_mode),
+        seed: cut.get_seed(),
+        elapsed: score.elapsed,
+        moves: score.moves,
+        hints: score.hints,
+        pieces: save_pieces(&pieces, &boards),
+    };
+    if let Err(e) = save.write(&save_file.0) {
+        warn!("can not save to {}: {e}", save_file.0.display());
+    }
+}
+
+/// where every piece lies, groups are numbered in order of appearance
+pub(super) fn save_pieces(
+    pieces: &Query<SavedPieceData>,
+    boards: &Query<&Board>,
+) -> Vec<SavedPiece> {
+    let mut groups = vec![];
+    pieces
+        .iter()
+        .map(
+            |(piece, transform, orientation, above, in_group)| SavedPiece {
+                correct_index: piece.correct_index,
+                x: transform.translation.x,
+                y: transform.translation.y,
+                orientation: orientation.0,
+                above: above.map(|above| boards.get(above.0).unwrap().index),
+                group: in_group.map(|in_group| {
+                    groups
+                        .iter()
+                        .position(|group| *group == in_group.0)
+                        .unwrap_or_else(|| {
+                            groups.push(in_group.0);
+                            groups.len() - 1
+                        })
+                }),
+            },
+        )
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn save_on_exit(
+    mut exit: EventReader<AppExit>,
+    save_file: Res<SaveFile>,
+    level: Res<Levels>,
+    total_pieces: Res<TotalPieces>,
+    rotation_mode: Res<RotationMode>,
+    seed_entry: Res<SeedEntry>,
+    cut: Res<PuzzleCut>,
+    score: Res<Score>,
+    pieces: Query<SavedPieceData>,
+    boards: Query<&Board>,
+) {
+    if exit.read().next().is_some() {
+        save_game(
+            save_file,
+            level,
+            total_pieces,
+            rotation_mode,
+            seed_entry,
+            cut,
+            score,
+            pieces,
+            boards,
+        );
+    }
+}
+
+/// the last drop may already have solved the puzzle, its save is discarded then
+pub(super) fn not_solved(next_state: Res<NextState<GameState>>) -> bool {
+    !matches!(*next_state, NextState::Pending(GameState::Success))
+}
+
+pub(super) fn discard_save(_: Trigger<Success>, save_file: Res<SaveFile>) {
+    SaveGame::discard(&save_file.0);
+}
+
+/// put pieces back where the save left them
+pub(super) fn restore_game(
+    mut commands: Commands,
+    restore: Res<RestoreGame>,
+    mut pieces: Query<RestoredPieceData>,
+    boards: Query<(Entity, &Board)>,
+) {
+    restore_pieces(&mut commands, &restore.0.pieces, &mut pieces, &boards);
+    commands.remove_resource::<RestoreGame>();
+}
+
+pub(super) type RestoredPieceData = (
+    Entity,
+    &'static Piece,
+    &'static mut Transform,
+    &'static mut Orientation,
+    Option<&'static InGroup>,
+);
+
+/// move every piece to its saved place, with its board slot and group
+pub(super) fn restore_pieces(
+    commands: &mut Commands,
+    saved_pieces: &[SavedPiece],
+    pieces: &mut Query<RestoredPieceData>,
+    boards: &Query<(Entity, &Board)>,
+) {
+    // drop every relation first, a slot takes only one piece at a time
+    let mut old_groups = vec![];
+    for (entity, .., in_group) in pieces.iter() {
+        commands
+            .entity(entity)
+            .remove::<(Above, PreAbove, InGroup, Moving)>();
+        if let Some(in_group) = in_group
+            && !old_groups.contains(&in_group.0)
+        {
+            old_groups.push(in_group.0);
+        }
+    }
+    for group in old_groups {
+        commands.entity(group).despawn();
+    }
+
+    let mut groups: Vec<Entity> = vec![];
+    for (entity, piece, mut transform, mut orientation, _) in pieces.iter_mut() {
+        let Some(saved) = saved_pieces
+            .iter()
+            .find(|saved| saved.correct_index == piece.correct_index)
+        else {
+            continue;
+        };
+
+        *orientation = Orientation(saved.orientation % 4);
+        transform.translation.x = saved.x;
+        transform.translation.y = saved.y;
+        transform.rotation = Quat::from_rotation_z(orientation.get_angle());
+
+        if let Some(board) = saved
+            .above
+            .and_then(|index| boards.iter().find(|board| board.1.index == index))
+        {
+            commands.entity(entity).insert(Above(board.0));
+        }
+        if let Some(group) = saved.group {
+            while groups.len() <= group {
+                groups.push(commands.spawn(OnPlayScreen).id());
+            }
+            commands.entity(entity).insert(InGroup(groups[group]));
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::play::piece::Piece;
+use crate::play::pointer::Cursor;
+use crate::play::{MoveState, OnPlayScreen};
+use bevy::prelude::*;
+
+pub(super) const SELECTED_TINT: Color = Color::srgb(0.6, 0.8, 1.);
+const SELECTION_BOX_COLOR: Color = Color::srgba(0.3, 0.5, 1., 0.25);
+// above every piece
+const SELECTION_BOX_Z: f32 = 10.;
+
+/// piece taken along with any other selected piece
+#[derive(Component)]
+pub struct Selected;
+
+/// rectangle being drawn with shift and the primary button, from its first corner
+#[derive(Component)]
+pub(super) struct SelectionBox(Vec2);
+
+/// shift draws a selection box and ctrl toggles a piece, neither takes pieces
+pub fn selecting(keys: &ButtonInput<KeyCode>) -> bool {
+    keys.any_pressed([
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+    ])
+}
+
+/// ctrl-click adds a piece to the selection or takes it out
+pub(super) fn toggle_selected(
+    click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<State<MoveState>>,
+    pieces: Query<Has<Selected>, With<Piece>>,
+) {
+    if click.button != PointerButton::Primary
+        || *state.get() != MoveState::Init
+        || !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        return;
+    }
+    match pieces.get(click.target) {
+        Ok(true) => {
+            commands.entity(click.target).remove::<Selected>();
+        }
+        Ok(false) => {
+            commands.entity(click.target).insert(Selected);
+        }
+        Err(_) => {}
+    }
+}
+
+/// shift-drag on the table selects every piece inside the rectangle, escape clears it
+pub(super) fn select_box(
+    mut commands: Commands,
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    cursor: Res<Cursor>,
+    mut selection_box: Query<(Entity, &SelectionBox, &mut Transform, &mut Sprite), Without<Piece>>,
+    pieces: Query<(Entity, &Transform, Has<Selected>), With<Piece>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        for (entity, _, selected) in pieces.iter() {
+            if selected {
+                commands.entity(entity).remove::<Selected>();
+            }
+        }
+    }
+
+    let Some(cursor) = cursor.0 else {
+        return;
+    };
+
+    let Ok((entity, start, mut transform, mut sprite)) = selection_box.single_mut() else {
+        if buttons.just_pressed(MouseButton::Left)
+            && keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+        {
+            commands.spawn((
+                SelectionBox(cursor),
+                Sprite::from_color(SELECTION_BOX_COLOR, Vec2::ZERO),
+                Transform::from_translation(cursor.extend(SELECTION_BOX_Z)),
+                Pickable::IGNORE,
+                OnPlayScreen,
+            ));
+        }
+        return;
+    };
+
+    let rect = Rect::from_corners(start.0, cursor);
+    if buttons.pressed(MouseButton::Left) {
+        transform.translation = rect.center().extend(SELECTION_BOX_Z);
+        sprite.custom_size = Some(rect.size());
+        return;
+    }
+
+    for (piece, piece_transform, selected) in pieces.iter() {
+        let inside = rect.contains(piece_transform.translation.truncate());
+        if inside && !selected {
+            commands.entity(piece).insert(Selected);
+        } else if !inside && selected {
+            commands.entity(piece).remove::<Selected>();
+        }
+    }
+    commands.entity(entity).despawn();
+}
+
+pub(super) fn draw_selected(
+    added: Query<Entity, Added<Selected>>,
+    mut removed: RemovedComponents<Selected>,
+    mut sprites: Query<&mut Sprite, With<Piece>>,
+) {
+    for entity in added.iter() {
+        if let Ok(mut sprite) = sprites.get_mut(entity) {
+            sprite.color = SELECTED_TINT;
+        }
+    }
+    for entity in removed.read() {
+        if let Ok(mut sprite) = sprites.get_mut(entity) {
+            sprite.color = Color::WHITE;
+        }
+    }
+}
@@ -0,0 +1,524 @@
+//! the game run headless: no window and no renderer, pointer events are sent by hand and the
+//! pointer position is set through `Cursor`
+
+use super::*;
+use crate::config::hint::HintLimit;
+use crate::config::input_mode::InputMode;
+use crate::config::level::Levels;
+use crate::config::preview::{GhostOpacity, PreviewModes};
+use crate::config::seed::{PuzzleSeed, SeedEntry};
+use crate::config::total_pieces::TotalPieces;
+use crate::config::undo::UndoDepth;
+use crate::loading::loading_plugin;
+use crate::play::board::BoardSlots;
+use crate::play::piece::Piece;
+use crate::play::pointer::Cursor;
+use crate::play::preview::PreviewWindow;
+use crate::play::result::Score;
+use crate::play::save::{SaveFile, SaveGame};
+use crate::stats::NewRecord;
+use crate::success::success_plugin;
+use bevy::asset::UnapprovedPathMode;
+use bevy::image::{CompressedImageFormats, ImageLoader};
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::mouse::{MouseButtonInput, MouseMotion};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::picking::backend::HitData;
+use bevy::picking::pointer::{Location, PointerId};
+use bevy::render::camera::{ManualTextureViewHandle, NormalizedRenderTarget};
+use bevy::state::app::StatesPlugin;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const PICTURE: &str = "assets/resources/1.jpg";
+// frames allowed for the picture to load and be cut
+const LOADING_FRAMES: usize = 2000;
+
+/// number of `Success` events seen
+#[derive(Resource, Default)]
+struct Successes(usize);
+
+/// the game without window or renderer, in the config state with a 6 piece level;
+/// the save goes to a file of its own so runs do not touch `save.ron`
+fn app(name: &str) -> App {
+    let mut levels = Levels::default();
+    levels.select_path(PICTURE);
+    let save = std::env::temp_dir().join(format!("jigsaw-{name}-{}.ron", std::process::id()));
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin {
+            unapproved_path_mode: UnapprovedPathMode::Deny,
+            ..default()
+        },
+        StatesPlugin,
+        InputPlugin,
+    ))
+    .init_asset::<Image>()
+    .init_asset::<Mesh>()
+    .init_asset::<ColorMaterial>()
+    .init_asset::<Font>()
+    .register_asset_loader(ImageLoader::new(CompressedImageFormats::NONE))
+    .insert_resource(levels)
+    .insert_resource(SaveFile(save))
+    .insert_resource(TotalPieces::P6)
+    .init_resource::<RotationMode>()
+    .init_resource::<InputMode>()
+    .init_resource::<UndoDepth>()
+    .init_resource::<HintLimit>()
+    .init_resource::<PreviewModes>()
+    .init_resource::<GhostOpacity>()
+    .init_resource::<SeedEntry>()
+    .insert_resource(NewRecord(false))
+    .init_resource::<Successes>()
+    .init_state::<GameState>()
+    .add_plugins((loading_plugin, play_plugin, success_plugin))
+    .add_observer(|_: Trigger<Success>, mut successes: ResMut<Successes>| {
+        successes.0 += 1;
+    });
+    app.update();
+    app
+}
+
+fn game_state(app: &App) -> GameState {
+    app.world().resource::<State<GameState>>().get().clone()
+}
+
+fn move_state(app: &App) -> MoveState {
+    app.world().resource::<State<MoveState>>().get().clone()
+}
+
+/// go through loading the way the start button does, until the pieces are out
+fn start(app: &mut App) {
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Loading);
+    for _ in 0..LOADING_FRAMES {
+        app.update();
+        if game_state(app) == GameState::Play {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("still {:?} after {LOADING_FRAMES} frames", game_state(app));
+}
+
+/// piece entities by their correct index
+fn pieces(app: &mut App) -> Vec<Entity> {
+    let mut pieces: Vec<(usize, Entity)> = app
+        .world_mut()
+        .query::<(Entity, &Piece)>()
+        .iter(app.world())
+        .map(|(entity, piece)| (piece.correct_index, entity))
+        .collect();
+    pieces.sort();
+    pieces.into_iter().map(|(_, entity)| entity).collect()
+}
+
+fn position(app: &App, entity: Entity) -> Vec2 {
+    app.world()
+        .get::<Transform>(entity)
+        .unwrap()
+        .translation
+        .truncate()
+}
+
+fn slot(app: &App, index: usize) -> Entity {
+    app.world().resource::<BoardSlots>().get(index).unwrap()
+}
+
+fn slot_center(app: &App, index: usize) -> Vec2 {
+    app.world().resource::<BoardLayout>().get_slot_center(index)
+}
+
+/// primary click on `target` with the pointer at `at`
+fn click(app: &mut App, target: Entity, at: Vec2) {
+    app.world_mut().resource_mut::<Cursor>().0 = Some(at);
+    let location = Location {
+        target: NormalizedRenderTarget::TextureView(ManualTextureViewHandle(0)),
+        position: Vec2::ZERO,
+    };
+    let click = Click {
+        button: PointerButton::Primary,
+        hit: HitData::new(Entity::PLACEHOLDER, 0., None, None),
+        duration: Duration::ZERO,
+    };
+    app.world_mut().trigger_targets(
+        Pointer::new(PointerId::Mouse, location, target, click),
+        target,
+    );
+    app.update();
+}
+
+/// press or let go `key`, for one frame
+fn key(app: &mut App, key: KeyCode, character: &str, state: ButtonState) {
+    app.world_mut().send_event(KeyboardInput {
+        key_code: key,
+        logical_key: Key::Character(character.into()),
+        state,
+        text: None,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+}
+
+/// press `key` for one frame and let it go
+fn press(app: &mut App, key_code: KeyCode, character: &str) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        key(app, key_code, character, state);
+    }
+}
+
+/// take a piece where it lies, carry it to `to` and drop it there
+fn carry(app: &mut App, piece: Entity, to: Vec2) {
+    let from = position(app, piece);
+    click(app, piece, from);
+    assert_eq!(move_state(app), MoveState::Move);
+
+    app.world_mut().resource_mut::<Cursor>().0 = Some(to);
+    app.update();
+    assert_eq!(position(app, piece), to);
+
+    click(app, piece, to);
+    assert_eq!(move_state(app), MoveState::Init);
+}
+
+/// put every piece in its slot
+fn solve(app: &mut App) {
+    for (index, piece) in pieces(app).into_iter().enumerate() {
+        let center = slot_center(app, index);
+        carry(app, piece, center);
+    }
+}
+
+/// the success screen button showing `label`
+fn button(app: &mut App, label: &str) -> Entity {
+    let mut buttons = app
+        .world_mut()
+        .query_filtered::<(Entity, &Children), With<Button>>();
+    let world = app.world();
+    buttons
+        .iter(world)
+        .find(|(_, children)| {
+            children
+                .iter()
+                .any(|child| world.get::<Text>(child).is_some_and(|text| text.0 == label))
+        })
+        .map(|(entity, _)| entity)
+        .unwrap_or_else(|| panic!("no {label} button"))
+}
+
+fn remove_save(app: &App) {
+    let save: &PathBuf = &app.world().resource::<SaveFile>().0;
+    let _ = std::fs::remove_file(save);
+}
+
+#[test]
+fn pieces_start_in_the_trays() {
+    let mut app = app("trays");
+    start(&mut app);
+
+    let pieces = pieces(&mut app);
+    assert_eq!(pieces.len(), 6);
+    let layout = *app.world().resource::<BoardLayout>();
+    for piece in pieces {
+        let position = position(&app, piece);
+        assert!(
+            position.x.abs() > layout.get_board_width() / 2.,
+            "piece at {position} lies on the board"
+        );
+        assert!(app.world().get::<Above>(piece).is_none());
+    }
+    assert_eq!(move_state(&app), MoveState::Init);
+    remove_save(&app);
+}
+
+#[test]
+fn drop_on_a_slot_takes_it() {
+    let mut app = app("slot");
+    start(&mut app);
+
+    let piece = pieces(&mut app)[2];
+    let center = slot_center(&app, 2);
+    carry(&mut app, piece, center + Vec2::new(2., -1.));
+
+    let above = app.world().get::<Above>(piece).expect("piece on the board");
+    assert_eq!(above.0, slot(&app, 2));
+    // snapped to the slot center
+    assert_eq!(position(&app, piece), center);
+    assert!(app.world().get::<PreAbove>(piece).is_none());
+    assert_eq!(app.world().resource::<Score>().moves, 2);
+    remove_save(&app);
+}
+
+#[test]
+fn pieces_over_one_slot_preselect_it_once() {
+    let mut app = app("one-slot");
+    start(&mut app);
+
+    // two pieces in hand held at the same spot, as a selection carried apart can be
+    let held = &pieces(&mut app)[..2];
+    for piece in held {
+        app.world_mut()
+            .entity_mut(*piece)
+            .insert(Moving(Vec2::ZERO));
+    }
+    app.world_mut()
+        .resource_mut::<NextState<MoveState>>()
+        .set(MoveState::Move);
+    app.world_mut().resource_mut::<Cursor>().0 = Some(slot_center(&app, 0));
+    app.update();
+    app.update();
+
+    let preselected: Vec<Entity> = held
+        .iter()
+        .filter_map(|piece| app.world().get::<PreAbove>(*piece))
+        .map(|pre_above| pre_above.0)
+        .collect();
+    assert_eq!(preselected, vec![slot(&app, 0)]);
+    remove_save(&app);
+}
+
+#[test]
+fn drop_off_the_board_takes_no_slot() {
+    let mut app = app("table");
+    start(&mut app);
+
+    let piece = pieces(&mut app)[0];
+    let layout = *app.world().resource::<BoardLayout>();
+    let beside = Vec2::new(layout.get_board_width(), 0.);
+    carry(&mut app, piece, beside);
+
+    assert!(app.world().get::<Above>(piece).is_none());
+    assert_eq!(position(&app, piece), beside);
+    remove_save(&app);
+}
+
+#[test]
+fn taking_a_placed_piece_frees_its_slot() {
+    let mut app = app("free");
+    start(&mut app);
+
+    let piece = pieces(&mut app)[1];
+    let center = slot_center(&app, 1);
+    carry(&mut app, piece, center);
+    assert!(app.world().get::<Above>(piece).is_some());
+
+    click(&mut app, piece, center);
+    assert_eq!(move_state(&app), MoveState::Move);
+    assert!(app.world().get::<Above>(piece).is_none());
+    assert!(app.world().get::<Under>(slot(&app, 1)).is_none());
+    remove_save(&app);
+}
+
+#[test]
+fn taking_a_placed_piece_takes_its_placed_neighbours() {
+    let mut app = app("around");
+    start(&mut app);
+
+    // 3 x 2 board: 0 and 1 share a side, 5 is not next to 0
+    let pieces = pieces(&mut app);
+    for index in [0, 1, 5] {
+        let center = slot_center(&app, index);
+        carry(&mut app, pieces[index], center);
+    }
+
+    let center = slot_center(&app, 0);
+    click(&mut app, pieces[0], center);
+    assert!(app.world().get::<Moving>(pieces[0]).is_some());
+    assert!(app.world().get::<Moving>(pieces[1]).is_some());
+    assert!(app.world().get::<Moving>(pieces[5]).is_none());
+    assert!(app.world().get::<Above>(pieces[5]).is_some());
+    remove_save(&app);
+}
+
+#[test]
+fn wrong_slots_do_not_solve() {
+    let mut app = app("wrong");
+    start(&mut app);
+
+    // every piece one slot further along
+    let pieces = pieces(&mut app);
+    for (index, piece) in pieces.iter().enumerate() {
+        let center = slot_center(&app, (index + 1) % pieces.len());
+        carry(&mut app, *piece, center);
+    }
+
+    assert_eq!(app.world().resource::<Successes>().0, 0);
+    assert_eq!(game_state(&app), GameState::Play);
+    remove_save(&app);
+}
+
+#[test]
+fn solving_fires_success_once() {
+    let mut app = app("solve");
+    start(&mut app);
+    solve(&mut app);
+
+    assert_eq!(app.world().resource::<Successes>().0, 1);
+    assert_eq!(game_state(&app), GameState::Success);
+    // a solved puzzle leaves no save behind
+    assert!(!app.world().resource::<SaveFile>().0.exists());
+}
+
+#[test]
+fn p_hides_and_shows_the_preview_window() {
+    let mut app = app("preview");
+    start(&mut app);
+
+    let mut windows = app
+        .world_mut()
+        .query_filtered::<&Visibility, With<PreviewWindow>>();
+    assert_eq!(windows.single(app.world()).unwrap(), Visibility::Inherited);
+    press(&mut app, KeyCode::KeyP, "p");
+    assert_eq!(windows.single(app.world()).unwrap(), Visibility::Hidden);
+    press(&mut app, KeyCode::KeyP, "p");
+    assert_eq!(windows.single(app.world()).unwrap(), Visibility::Inherited);
+    remove_save(&app);
+}
+
+#[test]
+fn sorting_is_saved_and_undone() {
+    let mut app = app("sort");
+    start(&mut app);
+    let pieces = pieces(&mut app);
+    let positions =
+        |app: &App| -> Vec<Vec2> { pieces.iter().map(|piece| position(app, *piece)).collect() };
+    let scattered = positions(&app);
+
+    press(&mut app, KeyCode::KeyS, "s");
+    let sorted = positions(&app);
+    assert_ne!(sorted, scattered);
+    let mut saved = SaveGame::load(&app.world().resource::<SaveFile>().0)
+        .unwrap()
+        .pieces;
+    saved.sort_by_key(|piece| piece.correct_index);
+    let saved: Vec<Vec2> = saved
+        .iter()
+        .map(|piece| Vec2::new(piece.x, piece.y))
+        .collect();
+    assert_eq!(saved, sorted);
+
+    // one undo takes the sort back
+    key(&mut app, KeyCode::ControlLeft, "", ButtonState::Pressed);
+    press(&mut app, KeyCode::KeyZ, "z");
+    key(&mut app, KeyCode::ControlLeft, "", ButtonState::Released);
+    assert_eq!(positions(&app), scattered);
+    remove_save(&app);
+}
+
+#[test]
+fn space_pans_with_a_button_held_only() {
+    let mut app = app("pan");
+    start(&mut app);
+    let camera = app.world_mut().spawn(Camera2d).id();
+    let camera_x = |app: &App| app.world().get::<Transform>(camera).unwrap().translation.x;
+    let moved = |app: &mut App| {
+        app.world_mut().send_event(MouseMotion {
+            delta: Vec2::new(10., 0.),
+        });
+        app.update();
+    };
+    let mouse = |app: &mut App, button: MouseButton, state: ButtonState| {
+        app.world_mut().send_event(MouseButtonInput {
+            button,
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    };
+
+    key(&mut app, KeyCode::Space, " ", ButtonState::Pressed);
+    moved(&mut app);
+    assert_eq!(camera_x(&app), 0.);
+
+    mouse(&mut app, MouseButton::Left, ButtonState::Pressed);
+    moved(&mut app);
+    assert_eq!(camera_x(&app), -10.);
+    mouse(&mut app, MouseButton::Left, ButtonState::Released);
+    key(&mut app, KeyCode::Space, " ", ButtonState::Released);
+
+    // the middle button pans on its own
+    mouse(&mut app, MouseButton::Middle, ButtonState::Pressed);
+    moved(&mut app);
+    assert_eq!(camera_x(&app), -20.);
+    remove_save(&app);
+}
+
+#[test]
+fn same_seed_same_table() {
+    let tables: Vec<Vec<(Vec2, Orientation)>> = ["seed-a", "seed-b"]
+        .into_iter()
+        .map(|name| {
+            let mut app = app(name);
+            app.world_mut().resource_mut::<RotationMode>().0 = true;
+            app.world_mut().resource_mut::<SeedEntry>().text = "1234".to_string();
+            start(&mut app);
+            assert_eq!(app.world().resource::<PuzzleSeed>().0, 1234);
+            let table = pieces(&mut app)
+                .into_iter()
+                .map(|piece| {
+                    let orientation = *app.world().get::<Orientation>(piece).unwrap();
+                    (position(&app, piece), orientation)
+                })
+                .collect();
+            remove_save(&app);
+            table
+        })
+        .collect();
+    assert_eq!(tables[0], tables[1]);
+}
+
+#[test]
+fn no_level_goes_back_to_config() {
+    let mut app = app("nolevel");
+    app.insert_resource(Levels::default());
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Loading);
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(game_state(&app), GameState::Config);
+    assert!(!app.world().resource::<Levels>().get_errors().is_empty());
+}
+
+#[test]
+fn config_play_success_play() {
+    let mut app = app("flow");
+    assert_eq!(game_state(&app), GameState::Config);
+
+    start(&mut app);
+    solve(&mut app);
+    assert_eq!(game_state(&app), GameState::Success);
+
+    let play_again = button(&mut app, "Play again");
+    click(&mut app, play_again, Vec2::ZERO);
+    assert_eq!(game_state(&app), GameState::Loading);
+    for _ in 0..LOADING_FRAMES {
+        if game_state(&app) == GameState::Play {
+            break;
+        }
+        app.update();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(game_state(&app), GameState::Play);
+
+    // a fresh puzzle: nothing left from the first one
+    let pieces = pieces(&mut app);
+    assert_eq!(pieces.len(), 6);
+    assert!(
+        pieces
+            .iter()
+            .all(|piece| app.world().get::<Above>(*piece).is_none())
+    );
+    assert_eq!(app.world().resource::<Score>().moves, 0);
+    assert_eq!(move_state(&app), MoveState::Init);
+
+    // and it can be solved again, firing one more success
+    solve(&mut app);
+    assert_eq!(app.world().resource::<Successes>().0, 2);
+    assert_eq!(game_state(&app), GameState::Success);
+}
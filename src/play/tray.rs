@@ -0,0 +1,239 @@
+use crate::config::seed::PuzzleSeed;
+use crate::play::cut::PuzzleCut;
+use crate::play::layout::BoardLayout;
+use crate::play::piece::{PIECE_Z, Piece};
+use crate::play::{Above, InGroup, Moving, OnPlayScreen, PuzzleImage};
+use bevy::color::Hsva;
+use bevy::prelude::*;
+use image::{DynamicImage, GenericImageView};
+use rand::Rng;
+
+// distance between two pieces lying in a tray, relative to a piece with its knobs
+const TRAY_SPACING: f32 = 1.1;
+// places per piece in the trays filled at the start, the pieces are thrown in at random
+const SCATTER_ROOM: f32 = 2.;
+// random sequence of the puzzle seed throwing the pieces in the trays
+const SCATTER_STREAM: u64 = 1;
+// random sequence of the puzzle seed mixing the pieces of a tray when sorting
+const SORT_STREAM: u64 = 5;
+const TRAY_COLOR: Color = Color::srgba(0., 0., 0., 0.08);
+// under the pieces, trays never cover the board
+const TRAY_Z: f32 = 0.2;
+// pixels read along each side of a cell to find its color
+const COLOR_SAMPLES: u32 = 16;
+const HUE_SECTORS: usize = 6;
+// dark, greyish, then one group per hue sector
+const COLOR_GROUPS: usize = 2 + HUE_SECTORS;
+
+/// table area beside the board holding loose pieces
+#[derive(Component)]
+pub(super) struct Tray;
+
+/// distance between the centers of two pieces side by side in a tray
+fn get_pitch(layout: &BoardLayout) -> f32 {
+    let (width, height) = (layout.get_cell_width(), layout.get_cell_height());
+    (width.max(height) + 2. * PuzzleCut::get_padding(width, height)) * TRAY_SPACING
+}
+
+/// trays holding `places[i]` pieces each, on the left and right of the board in turn,
+/// moving away from it as they are added
+fn get_tray_rects(layout: &BoardLayout, pitch: f32, places: &[usize]) -> Vec<Rect> {
+    let most = places.iter().copied().max().unwrap_or(0) as f32;
+    let height = layout
+        .get_board_height()
+        .max(most.sqrt() * pitch)
+        .max(pitch);
+    let per_column = (height / pitch).floor() as usize;
+
+    let mut left = -layout.get_board_width() / 2. - pitch / 2.;
+    let mut right = layout.get_board_width() / 2. + pitch / 2.;
+    places
+        .iter()
+        .enumerate()
+        .map(|(index, places)| {
+            let width = places.div_ceil(per_column).max(1) as f32 * pitch;
+            let min_x = if index % 2 == 0 {
+                left -= width;
+                let min_x = left;
+                left -= pitch / 2.;
+                min_x
+            } else {
+                let min_x = right;
+                right += width + pitch / 2.;
+                min_x
+            };
+            Rect::new(min_x, -height / 2., min_x + width, height / 2.)
+        })
+        .collect()
+}
+
+/// center of place `index` in a tray, filled column by column from the top left
+fn get_place(tray: Rect, pitch: f32, index: usize) -> Vec2 {
+    let per_column = (tray.height() / pitch).floor().max(1.) as usize;
+    let column = index / per_column;
+    let row = index % per_column;
+    Vec2::new(
+        tray.min.x + (column as f32 + 0.5) * pitch,
+        tray.max.y - (row as f32 + 0.5) * pitch,
+    )
+}
+
+fn get_random_place(tray: Rect, pitch: f32, rng: &mut impl Rng) -> Vec2 {
+    let inner = tray.inflate(-pitch / 2.);
+    Vec2::new(
+        rng.gen_range(inner.min.x, inner.max.x.max(inner.min.x + 1.)),
+        rng.gen_range(inner.min.y, inner.max.y.max(inner.min.y + 1.)),
+    )
+}
+
+fn spawn_trays(commands: &mut Commands, rects: &[Rect]) {
+    for rect in rects {
+        commands.spawn((
+            Sprite::from_color(TRAY_COLOR, rect.size()),
+            Transform::from_translation(rect.center().extend(TRAY_Z)),
+            Pickable::IGNORE,
+            Tray,
+            OnPlayScreen,
+        ));
+    }
+}
+
+/// spawn two trays and throw the pieces in them, half each, returns the position of every piece;
+/// the same seed always throws them the same way
+pub(super) fn scatter_in_trays(
+    commands: &mut Commands,
+    layout: &BoardLayout,
+    seed: &PuzzleSeed,
+) -> Vec<Vec3> {
+    let count = layout.get_count();
+    let pitch = get_pitch(layout);
+    let places: Vec<usize> = [count.div_ceil(2), count / 2]
+        .iter()
+        .map(|pieces| (*pieces as f32 * SCATTER_ROOM).ceil() as usize)
+        .collect();
+    let rects = get_tray_rects(layout, pitch, &places);
+    spawn_trays(commands, &rects);
+
+    let mut rng = seed.get_rng(SCATTER_STREAM);
+    let mut order: Vec<usize> = (0..count).collect();
+    rng.shuffle(&mut order);
+    let mut positions = vec![Vec3::ZERO; count];
+    for (turn, index) in order.into_iter().enumerate() {
+        positions[index] = get_random_place(rects[turn % 2], pitch, &mut rng).extend(PIECE_Z);
+    }
+    positions
+}
+
+/// pieces were sorted into new trays, recorded and saved like a drop
+#[derive(Event)]
+pub(super) struct Sorted;
+
+/// pieces lying on their own on the table, neither on the board nor in a group nor in hand
+type LoosePiece = (Without<Above>, Without<InGroup>, Without<Moving>);
+
+/// S sorts the loose pieces into a tray of edge pieces and a tray of inner pieces,
+/// C sorts them into one tray per color; pieces on the board or in a group stay where they are
+#[allow(clippy::too_many_arguments)]
+pub(super) fn sort_pieces(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    layout: Res<BoardLayout>,
+    puzzle_image: Res<PuzzleImage>,
+    trays: Query<Entity, With<Tray>>,
+    mut pieces: Query<(&Piece, &mut Transform), LoosePiece>,
+    seed: Res<PuzzleSeed>,
+    mut sorted: EventWriter<Sorted>,
+) {
+    let by_color = keys.just_pressed(KeyCode::KeyC);
+    if !by_color && !keys.just_pressed(KeyCode::KeyS) {
+        return;
+    }
+    let group_of = |index: usize| {
+        if by_color {
+            puzzle_image.color_groups[index]
+        } else {
+            usize::from(!layout.is_edge(index))
+        }
+    };
+
+    let mut loose: Vec<_> = pieces.iter_mut().collect();
+    if loose.is_empty() {
+        return;
+    }
+    // the order in a tray should not tell where pieces belong
+    seed.get_rng(SORT_STREAM).shuffle(&mut loose);
+
+    let mut places = vec![0; COLOR_GROUPS];
+    for (piece, _) in loose.iter() {
+        places[group_of(piece.correct_index)] += 1;
+    }
+    // empty groups get no tray
+    let tray_of: Vec<Option<usize>> = places
+        .iter()
+        .scan(0, |next, places| {
+            Some((*places > 0).then(|| {
+                *next += 1;
+                *next - 1
+            }))
+        })
+        .collect();
+    places.retain(|places| *places > 0);
+
+    let pitch = get_pitch(&layout);
+    let rects = get_tray_rects(&layout, pitch, &places);
+    for tray in trays.iter() {
+        commands.entity(tray).despawn();
+    }
+    spawn_trays(&mut commands, &rects);
+
+    let mut filled = vec![0; rects.len()];
+    for (piece, transform) in loose.iter_mut() {
+        let Some(tray) = tray_of[group_of(piece.correct_index)] else {
+            continue;
+        };
+        let place = get_place(rects[tray], pitch, filled[tray]);
+        filled[tray] += 1;
+        transform.translation.x = place.x;
+        transform.translation.y = place.y;
+    }
+    sorted.write(Sorted);
+}
+
+/// color group of every cell of the picture, the group covering most of the cell:
+/// dark, greyish or one of the hue sectors
+pub(crate) fn get_color_groups(img: &DynamicImage, columns: u32, rows: u32) -> Vec<usize> {
+    let (width, height) = img.dimensions();
+    let (cell_width, cell_height) = (width / columns, height / rows);
+    (0..columns * rows)
+        .map(|index| {
+            let (column, row) = (index % columns, index / columns);
+            let mut counts = [0; COLOR_GROUPS];
+            for sample_y in 0..COLOR_SAMPLES {
+                for sample_x in 0..COLOR_SAMPLES {
+                    let x =
+                        column * cell_width + (2 * sample_x + 1) * cell_width / (2 * COLOR_SAMPLES);
+                    let y =
+                        row * cell_height + (2 * sample_y + 1) * cell_height / (2 * COLOR_SAMPLES);
+                    let [red, green, blue, _] = img.get_pixel(x, y).0;
+                    counts[get_color_group(Srgba::rgb_u8(red, green, blue))] += 1;
+                }
+            }
+            counts
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, count)| **count)
+                .map_or(0, |(group, _)| group)
+        })
+        .collect()
+}
+
+fn get_color_group(color: Srgba) -> usize {
+    let hsva = Hsva::from(color);
+    if hsva.value < 0.25 {
+        0
+    } else if hsva.saturation < 0.2 {
+        1
+    } else {
+        2 + (hsva.hue / 360. * HUE_SECTORS as f32) as usize % HUE_SECTORS
+    }
+}
@@ -0,0 +1,286 @@
+use crate::config::level::Levels;
+use crate::config::rotation::RotationMode;
+use crate::config::seed::SeedEntry;
+use crate::config::total_pieces::TotalPieces;
+use crate::play::result::Score;
+use crate::{GameState, despawn_screen};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+mod screen;
+
+pub fn stats_plugin(app: &mut App) {
+    app.insert_resource(Records::load())
+        .insert_resource(NewRecord(false))
+        .add_systems(OnEnter(GameState::Success), record_success)
+        .add_systems(OnEnter(GameState::Stats), screen::setup_stats)
+        .add_systems(
+            OnExit(GameState::Stats),
+            despawn_screen::<screen::OnStatsScreen>,
+        );
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BestTime {
+    pub level: String,
+    pub pieces: TotalPieces,
+    /// turned pieces are a record of their own, older records were all upright
+    #[serde(default)]
+    pub rotation: bool,
+    pub seconds: f32,
+}
+
+/// one solved puzzle
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Completion {
+    pub level: String,
+    pub pieces: TotalPieces,
+    pub seconds: f32,
+    pub moves: u32,
+    pub rotation: bool,
+    /// seconds since the unix epoch
+    pub date: u64,
+}
+
+/// average time and moves over the solves of one piece count
+#[derive(Debug, Clone, Copy)]
+pub struct Average {
+    pub pieces: TotalPieces,
+    pub count: usize,
+    pub seconds: f32,
+    pub moves: f32,
+}
+
+/// fastest solve of every level, piece count and rotation, and every solve so far
+#[derive(Resource, Serialize, Deserialize, Debug, Default)]
+pub struct Records {
+    best: Vec<BestTime>,
+    #[serde(default)]
+    history: Vec<Completion>,
+}
+
+/// whether the last solved puzzle beat the best time
+#[derive(Resource)]
+pub struct NewRecord(pub bool);
+
+impl Records {
+    fn get_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("my_jigsaw_game").join("records.ron"))
+    }
+
+    fn load() -> Self {
+        let Some(path) = Self::get_path() else {
+            return Records::default();
+        };
+        match fs::read_to_string(&path) {
+            Ok(content) => ron::from_str(&content).unwrap_or_else(|e| {
+                warn!("can not parse {}: {e}", path.display());
+                Records::default()
+            }),
+            Err(_) => Records::default(),
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::get_path().ok_or("no data directory")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+        fs::write(path, content).map_err(|e| e.to_string())
+    }
+
+    /// best times of a level, smallest piece count first, upright before turned
+    pub fn get_best(&self, level: &str) -> Vec<&BestTime> {
+        let mut best: Vec<&BestTime> = self.best.iter().filter(|b| b.level == level).collect();
+        best.sort_by_key(|b| (b.pieces.get_value(), b.rotation));
+        best
+    }
+
+    /// best times of every level, grouped by level
+    pub fn get_all_best(&self) -> Vec<&BestTime> {
+        let mut best: Vec<&BestTime> = self.best.iter().collect();
+        best.sort_by(|a, b| {
+            (&a.level, a.pieces.get_value(), a.rotation).cmp(&(
+                &b.level,
+                b.pieces.get_value(),
+                b.rotation,
+            ))
+        });
+        best
+    }
+
+    /// solves, most recent first
+    pub fn get_history(&self) -> impl Iterator<Item = &Completion> {
+        self.history.iter().rev()
+    }
+
+    /// averages for every piece count solved at least once, smallest first
+    pub fn get_averages(&self) -> Vec<Average> {
+        let mut averages: Vec<Average> = vec![];
+        for completion in &self.history {
+            match averages
+                .iter_mut()
+                .find(|average| average.pieces == completion.pieces)
+            {
+                Some(average) => {
+                    average.count += 1;
+                    average.seconds += completion.seconds;
+                    average.moves += completion.moves as f32;
+                }
+                None => averages.push(Average {
+                    pieces: completion.pieces,
+                    count: 1,
+                    seconds: completion.seconds,
+                    moves: completion.moves as f32,
+                }),
+            }
+        }
+        for average in averages.iter_mut() {
+            average.seconds /= average.count as f32;
+            average.moves /= average.count as f32;
+        }
+        averages.sort_by_key(|average| average.pieces.get_value());
+        averages
+    }
+
+    /// add a solve to the history, returns whether it is a new best time
+    pub fn add(&mut self, completion: Completion) -> bool {
+        let new_record = self.submit(
+            &completion.level,
+            completion.pieces,
+            completion.rotation,
+            completion.seconds,
+        );
+        self.history.push(completion);
+        new_record
+    }
+
+    /// keep the time when it beats the current record, returns whether it did
+    pub fn submit(
+        &mut self,
+        level: &str,
+        pieces: TotalPieces,
+        rotation: bool,
+        seconds: f32,
+    ) -> bool {
+        match self
+            .best
+            .iter_mut()
+            .find(|b| b.level == level && b.pieces == pieces && b.rotation == rotation)
+        {
+            Some(best) if best.seconds <= seconds => false,
+            Some(best) => {
+                best.seconds = seconds;
+                true
+            }
+            None => {
+                self.best.push(BestTime {
+                    level: level.to_string(),
+                    pieces,
+                    rotation,
+                    seconds,
+                });
+                true
+            }
+        }
+    }
+}
+
+/// "m:ss" for a duration in seconds
+pub fn format_time(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// "yyyy-mm-dd" (utc) for seconds since the unix epoch
+pub fn format_date(date: u64) -> String {
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (date / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year}-{month:02}-{day:02}")
+}
+
+pub(crate) fn record_success(
+    mut records: ResMut<Records>,
+    mut new_record: ResMut<NewRecord>,
+    score: Res<Score>,
+    level: Res<Levels>,
+    total_pieces: Res<TotalPieces>,
+    rotation_mode: Res<RotationMode>,
+    seed_entry: Res<SeedEntry>,
+) {
+    let Some(level) = level.current_level() else {
+        return;
+    };
+    let date = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    new_record.0 = records.add(Completion {
+        level: level.get_path().to_string(),
+        pieces: *total_pieces,
+        seconds: score.elapsed,
+        moves: score.moves,
+        rotation: seed_entry.rotates(&rotation_mode),
+        date,
+    });
+    if let Err(e) = records.save() {
+        warn!("can not save records: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completion(rotation: bool, seconds: f32) -> Completion {
+        Completion {
+            level: "cat.jpg".to_string(),
+            pieces: TotalPieces::P24,
+            seconds,
+            moves: 30,
+            rotation,
+            date: 0,
+        }
+    }
+
+    #[test]
+    fn rotation_keeps_its_own_best_time() {
+        let mut records = Records::default();
+        assert!(records.add(completion(false, 60.)));
+        // slower, but the first solve with turned pieces
+        assert!(records.add(completion(true, 90.)));
+        assert!(!records.add(completion(true, 95.)));
+        assert!(records.add(completion(false, 50.)));
+
+        let best: Vec<(bool, f32)> = records
+            .get_best("cat.jpg")
+            .iter()
+            .map(|best| (best.rotation, best.seconds))
+            .collect();
+        assert_eq!(best, vec![(false, 50.), (true, 90.)]);
+    }
+
+    #[test]
+    fn older_best_times_are_upright() {
+        let records: Records =
+            ron::from_str(r#"(best: [(level: "cat.jpg", pieces: P24, seconds: 60.)])"#).unwrap();
+        assert!(!records.get_best("cat.jpg")[0].rotation);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::config::level::Levels;
+use crate::stats::{Records, format_date, format_time};
+use crate::{BUTTON_DEFAULT_BACKGROUND, GameState, TEXT_COLOR};
+use bevy::prelude::*;
+
+// older solves are only counted in the averages
+const HISTORY_SHOWN: usize = 15;
+
+#[derive(Component)]
+pub struct OnStatsScreen;
+
+fn level_title<'a>(levels: &'a Levels, path: &'a str) -> &'a str {
+    levels
+        .get_all()
+        .iter()
+        .find(|level| level.get_path() == path)
+        .map_or(path, |level| level.get_title())
+}
+
+pub fn setup_stats(mut commands: Commands, records: Res<Records>, levels: Res<Levels>) {
+    let parent = commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            OnStatsScreen,
+        ))
+        .id();
+
+    let mut lines = vec!["averages".to_string()];
+    let averages = records.get_averages();
+    if averages.is_empty() {
+        lines.push("no puzzle solved yet".to_string());
+    }
+    for average in averages {
+        lines.push(format!(
+            "{}: {} solved, {} and {:.0} moves on average",
+            average.pieces,
+            average.count,
+            format_time(average.seconds),
+            average.moves
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push("best times".to_string());
+    for best in records.get_all_best() {
+        lines.push(format!(
+            "{}  {}  {}  {}",
+            level_title(&levels, &best.level),
+            best.pieces,
+            format_time(best.seconds),
+            if best.rotation { "rotation" } else { "" }
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push("history".to_string());
+    for completion in records.get_history().take(HISTORY_SHOWN) {
+        lines.push(format!(
+            "{}  {}  {}  {}  {} moves  {}",
+            format_date(completion.date),
+            level_title(&levels, &completion.level),
+            completion.pieces,
+            format_time(completion.seconds),
+            completion.moves,
+            if completion.rotation { "rotation" } else { "" }
+        ));
+    }
+
+    let text = commands
+        .spawn((
+            Text::new(lines.join("\n")),
+            Node {
+                margin: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            OnStatsScreen,
+        ))
+        .id();
+
+    let back = commands
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(300.0),
+                height: Val::Px(65.0),
+                margin: UiRect::all(Val::Px(20.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_DEFAULT_BACKGROUND),
+            children![(Text::new("back"), TextColor(TEXT_COLOR))],
+            OnStatsScreen,
+        ))
+        .observe(back)
+        .id();
+
+    commands.entity(parent).add_children(&[text, back]);
+}
+
+fn back(_: Trigger<Pointer<Click>>, mut state: ResMut<NextState<GameState>>) {
+    state.set(GameState::Config);
+}
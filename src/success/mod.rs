@@ -1,14 +1,23 @@
 use crate::config::level::Levels;
+use crate::config::seed::{PuzzleSeed, SeedEntry};
+use crate::play::PuzzleImage;
+use crate::play::layout::BoardLayout;
+use crate::play::result::Score;
+use crate::stats::{NewRecord, record_success};
 use crate::{BUTTON_DEFAULT_BACKGROUND, GameState, TEXT_COLOR, despawn_screen};
 use bevy::app::App;
-use bevy::asset::RenderAssetUsages;
 use bevy::prelude::*;
 use bevy::ui::Display::Flex;
-use bevy::ui::Val::{Percent, Vw};
+use bevy::ui::Val::{Percent, Vh, Vw};
+
+// random sequence of the puzzle seed picking the next random level
+const LEVEL_STREAM: u64 = 3;
 
 pub fn success_plugin(app: &mut App) {
-    app.add_systems(OnEnter(GameState::Success), setup_success);
-    app.insert_resource(Levels::default());
+    app.add_systems(
+        OnEnter(GameState::Success),
+        setup_success.after(record_success),
+    );
     app.add_systems(
         OnExit(GameState::Success),
         despawn_screen::<OnSuccessScreen>,
@@ -18,7 +27,13 @@ pub fn success_plugin(app: &mut App) {
 #[derive(Component)]
 struct OnSuccessScreen;
 
-fn setup_success(mut commands: Commands, mut images: ResMut<Assets<Image>>, level: Res<Levels>) {
+fn setup_success(
+    mut commands: Commands,
+    puzzle_image: Res<PuzzleImage>,
+    score: Res<Score>,
+    layout: Res<BoardLayout>,
+    new_record: Res<NewRecord>,
+) {
     let button_node = Node {
         width: Val::Px(300.0),
         height: Val::Px(65.0),
@@ -43,19 +58,20 @@ fn setup_success(mut commands: Commands, mut images: ResMut<Assets<Image>>, leve
         ))
         .id();
 
-    let image_path = level.current_level().get_path();
-    let dyn_image = image::open(image_path).unwrap();
-    let image = images.add(Image::from_dynamic(
-        dyn_image,
-        true,
-        RenderAssetUsages::RENDER_WORLD,
-    ));
+    let aspect = layout.get_board_width() / layout.get_board_height();
+    // keep the picture shape, tall pictures are bounded by the window height
+    let (image_width, image_height) = if aspect > 1.5 {
+        (Vw(69.), Vw(69. / aspect))
+    } else {
+        (Vh(80. * aspect), Vh(80.))
+    };
+    let image = puzzle_image.source.clone();
 
     let left_image = commands
         .spawn((
             Node {
-                width: Vw(69.),
-                height: Vw(46.),
+                width: image_width,
+                height: image_height,
                 ..default()
             },
             ImageNode {
@@ -89,6 +105,22 @@ fn setup_success(mut commands: Commands, mut images: ResMut<Assets<Image>>, leve
         .entity(parent)
         .add_children(&[left_image, right_part]);
 
+    let mut result_text = score.get_text(layout.get_count());
+    if new_record.0 {
+        result_text.push_str("\nnew record");
+    }
+    let result = commands
+        .spawn((
+            Text::new(result_text),
+            TextColor(TEXT_COLOR),
+            Node {
+                margin: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            OnSuccessScreen,
+        ))
+        .id();
+
     let play_again = commands
         .spawn((
             Button,
@@ -133,31 +165,43 @@ fn setup_success(mut commands: Commands, mut images: ResMut<Assets<Image>>, leve
         .observe(exit)
         .id();
 
-    commands
-        .entity(right_part)
-        .add_children(&[play_again, play_in_order, play_random, exit]);
+    commands.entity(right_part).add_children(&[
+        result,
+        play_again,
+        play_in_order,
+        play_random,
+        exit,
+    ]);
 }
 
 fn play_again(_: Trigger<Pointer<Click>>, mut state: ResMut<NextState<GameState>>) {
-    state.set(GameState::Play);
+    state.set(GameState::Loading);
 }
 
 fn play_in_order(
     _: Trigger<Pointer<Click>>,
     mut level: ResMut<Levels>,
+    mut seed_entry: ResMut<SeedEntry>,
     mut state: ResMut<NextState<GameState>>,
 ) {
     level.next_level();
-    state.set(GameState::Play);
+    // another level is no longer the daily puzzle
+    seed_entry.daily = false;
+    state.set(GameState::Loading);
 }
 
 fn play_random(
     _: Trigger<Pointer<Click>>,
     mut level: ResMut<Levels>,
+    seed: Res<PuzzleSeed>,
+    mut seed_entry: ResMut<SeedEntry>,
     mut state: ResMut<NextState<GameState>>,
 ) {
-    level.random_level();
-    state.set(GameState::Play);
+    // the seed just played decides the next level, so a run of puzzles can be replayed
+    level.random_level(&mut seed.get_rng(LEVEL_STREAM));
+    // another level is no longer the daily puzzle
+    seed_entry.daily = false;
+    state.set(GameState::Loading);
 }
 
 fn exit(_: Trigger<Pointer<Click>>, mut exit: EventWriter<AppExit>) {